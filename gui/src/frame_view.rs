@@ -0,0 +1,93 @@
+use std::{cell::RefCell, rc::Rc};
+
+use gtk::cairo::{Context, Filter, Format, ImageSurface};
+use gtk::prelude::*;
+use relm4::gtk;
+use shared::data::Frame;
+
+/**
+ * Drawing area showing the latest frame. Each frame is uploaded once into a Cairo image
+ * surface which every redraw paints, so resizing or uncovering the window needs no render.
+ */
+pub struct FrameView {
+    area: gtk::DrawingArea,
+    surface: Rc<RefCell<Option<ImageSurface>>>,
+}
+
+impl FrameView {
+    pub fn new() -> Self {
+        let area = gtk::DrawingArea::new();
+        let surface: Rc<RefCell<Option<ImageSurface>>> = Rc::default();
+
+        let shown = surface.clone();
+        area.set_draw_func(move |_, cx, width, height| {
+            if let Some(surface) = shown.borrow().as_ref() {
+                paint(cx, surface, width, height);
+            }
+        });
+
+        Self { area, surface }
+    }
+
+    pub fn drawing_area(&self) -> &gtk::DrawingArea {
+        &self.area
+    }
+
+    /**
+     * Device pixels per logical pixel of the drawing area, frames should be rendered at
+     * this multiple of its size to look sharp on HiDPI screens
+     */
+    pub fn scale_factor(&self) -> i32 {
+        self.area.scale_factor()
+    }
+
+    /**
+     * Replaces the shown frame. Frames smaller than the area, like previews, are stretched.
+     */
+    pub fn show(&self, frame: &Frame) {
+        match to_surface(frame) {
+            Ok(surface) => {
+                self.surface.replace(Some(surface));
+                self.area.queue_draw();
+            }
+            Err(err) => eprintln!("Couldn't create image surface: {}", err),
+        }
+    }
+}
+
+/**
+ * Stretches surface over the area, which is given in logical pixels. Cairo maps them to
+ * device pixels, a frame rendered at device resolution is painted 1:1.
+ */
+fn paint(cx: &Context, surface: &ImageSurface, width: i32, height: i32) {
+    cx.scale(
+        width as f64 / surface.width() as f64,
+        height as f64 / surface.height() as f64,
+    );
+    if let Err(err) = cx.set_source_surface(surface, 0.0, 0.0) {
+        eprintln!("Couldn't paint frame: {}", err);
+        return;
+    }
+    cx.source().set_filter(Filter::Good);
+    cx.paint().expect("Couldn't paint frame");
+}
+
+/**
+ * Opaque 32-bit surface of the sRGB encoded frame. Alpha is ignored so the background the
+ * camera sees stays visible.
+ */
+fn to_surface(frame: &Frame) -> Result<ImageSurface, gtk::cairo::Error> {
+    let stride = Format::ARgb32.stride_for_width(frame.width as u32)?;
+    let mut data = vec![0; (stride * frame.height) as usize];
+
+    let width = frame.width as usize;
+    for (index, pixel) in frame.to_srgb8().iter().enumerate() {
+        let (x, y) = (index % width, index / width);
+        let argb =
+            0xff00_0000 | ((pixel.r as u32) << 16) | ((pixel.g as u32) << 8) | pixel.b as u32;
+        let offset = y * stride as usize + x * 4;
+        data[offset..offset + 4].copy_from_slice(&argb.to_ne_bytes());
+    }
+
+    ImageSurface::create_for_data(data, Format::ARgb32, frame.width, frame.height, stride)
+}
@@ -1,28 +1,102 @@
-use std::time::Duration;
+mod frame_view;
+mod navigation;
+mod outliner;
+mod render_worker;
 
-use gtk::cairo::Context;
+use std::time::{Duration, Instant};
+
+use frame_view::FrameView;
+use gtk::gdk;
 use gtk::prelude::RangeExt;
 use gtk::prelude::*;
+use navigation::Navigation;
+use outliner::{Outliner, Property, Selection};
 use relm4::component::{AsyncComponent, AsyncComponentParts};
-use relm4::drawing::DrawHandler;
 use relm4::{gtk, AsyncComponentSender, RelmApp, RelmWidgetExt};
-use renderer::renderer::renderer::Renderer;
-use shared::traits::Render;
+use render_worker::{RenderJob, RenderUpdate, RenderWorker};
+use renderer::renderer::denoise::DenoiseMethod;
+use renderer::scene::scene_description::SceneDescription;
+use renderer::{CameraDescription, FilmSettings, RenderSettings};
 
 const DEFAULT_SAMPLES_PER_PIXEL_VALUE: i16 = 10;
 
+/// Preview while moving is rendered at a fraction of the resolution with a single sample
+const PREVIEW_SCALE: i32 = 4;
+const PREVIEW_MAX_DEPTH: i16 = 8;
+/// Samples added by each refinement pass once the camera stops
+const REFINE_SAMPLES: i16 = 2;
+/// Camera must be still this long before refinement starts
+const SETTLE_TIME: Duration = Duration::from_millis(150);
+
+/// Radians per dragged pixel
+const ORBIT_SPEED: f64 = 0.01;
+const LOOK_SPEED: f64 = 0.004;
+/// Distance factor per scroll step
+const ZOOM_STEP: f32 = 1.1;
+/// Scene units per second, shift multiplies by FAST_FLY_FACTOR
+const FLY_SPEED: f32 = 1.5;
+const FAST_FLY_FACTOR: f32 = 4.0;
+
 #[derive(Debug)]
 enum Msg {
     Render,
-    Resize((i32, i32)),
+    /// Size of the drawing area in logical pixels and its scale factor
+    Resize((i32, i32), i32),
     SampleAmountChanged(f64),
+    DenoiseChanged(u32),
+    DragBegin(DragMode),
+    /// Offset from where the drag started
+    DragUpdate((f64, f64)),
+    DragEnd,
+    Zoom(f64),
+    KeyPressed(gdk::Key, gdk::ModifierType),
+    KeyReleased(gdk::Key),
+    Select(Selection),
+    PropertyChanged(Property, f64),
+}
+
+#[derive(Debug)]
+enum Cmd {
+    Tick,
+    Rendered(RenderUpdate),
+}
+
+/**
+ * Left button orbits, middle button or shift + left pans and right button looks around
+ */
+#[derive(Clone, Copy, Debug)]
+enum DragMode {
+    Orbit,
+    Pan,
+    Look,
+}
+
+struct Drag {
+    mode: DragMode,
+    last_offset: (f64, f64),
 }
 
 struct App {
-    width: i32,
-    height: i32,
-    handler: DrawHandler,
-    samples_per_pixel: i16,
+    frame_view: FrameView,
+    /// Resolution is the drawing area size in device pixels
+    settings: RenderSettings,
+    scale_factor: i32,
+    denoise: Option<DenoiseMethod>,
+    navigation: Navigation,
+    scene: SceneDescription,
+    outliner: Outliner,
+    selection: Selection,
+    drag: Option<Drag>,
+    held_keys: Vec<gdk::Key>,
+    fast_fly: bool,
+    last_tick: Instant,
+    last_camera_change: Instant,
+    preview_pending: bool,
+    /// Full quality render of the current view was queued
+    refine_submitted: bool,
+    worker: RenderWorker,
+    /// Latest job submitted to worker, frames of older ones are dropped
+    job: Option<u64>,
 }
 
 #[relm4::component(async)]
@@ -30,7 +104,7 @@ impl AsyncComponent for App {
     type Init = ();
     type Input = Msg;
     type Output = ();
-    type CommandOutput = ();
+    type CommandOutput = Cmd;
 
     view! {
       gtk::Window {
@@ -65,43 +139,137 @@ impl AsyncComponent for App {
               },
             },
 
+            gtk::Box {
+              set_orientation: gtk::Orientation::Vertical,
+
+              gtk::Label {
+                set_label: "Denoise"
+              },
+
+              gtk::DropDown::from_strings(&["None", "Bilateral", "À-trous"]) {
+                connect_selected_notify[sender] => move |dropdown| {
+                    sender.input(Msg::DenoiseChanged(dropdown.selected()));
+                }
+              },
+            },
+
             gtk::Button {
               set_label: "Render",
               connect_clicked => Msg::Render
             },
           },
 
-          #[local_ref]
-          area -> gtk::DrawingArea {
-            set_vexpand: true,
-            set_hexpand: true,
+          gtk::Label {
+            set_label: "Drag to orbit, shift + drag or middle button to pan, right button to look around, scroll to zoom, WASD + QE to fly",
+            set_xalign: 0.0,
+            set_wrap: true,
+          },
+
+          gtk::Box {
+            set_orientation: gtk::Orientation::Horizontal,
+            set_spacing: 10,
+
+            #[local_ref]
+            area -> gtk::DrawingArea {
+              set_vexpand: true,
+              set_hexpand: true,
+              set_focusable: true,
 
-            connect_resize[sender] => move |_, x, y| {
-                sender.input(Msg::Resize((x, y)));
-            }
+              connect_resize[sender] => move |area, x, y| {
+                  sender.input(Msg::Resize((x, y), area.scale_factor()));
+              },
+
+              connect_scale_factor_notify[sender] => move |area| {
+                  sender.input(Msg::Resize((area.width(), area.height()), area.scale_factor()));
+              }
+            },
+
+            gtk::ScrolledWindow {
+              set_hscrollbar_policy: gtk::PolicyType::Never,
+
+              #[local_ref]
+              outliner_panel -> gtk::Box {},
+            },
           },
         }
       }
     }
 
-    async fn update(&mut self, msg: Msg, _sender: AsyncComponentSender<Self>, _root: &Self::Root) {
-        let cx = self.handler.get_context();
-
+    async fn update(&mut self, msg: Msg, sender: AsyncComponentSender<Self>, _root: &Self::Root) {
         match msg {
             Msg::Render => {
-                let frame = tokio::spawn(render(self.width, self.height, self.samples_per_pixel))
-                    .await
-                    .unwrap();
-                draw(&cx, frame);
+                self.refine_submitted = false;
             }
-            Msg::Resize((x, y)) => {
-                self.width = x;
-                self.height = y;
+            Msg::Resize((x, y), scale_factor) => {
+                self.settings.width = x * scale_factor;
+                self.settings.height = y * scale_factor;
+                self.scale_factor = scale_factor;
+                self.camera_changed();
             }
             Msg::SampleAmountChanged(new_val) => {
-                self.samples_per_pixel = new_val as i16;
-                println!("Samples changed to {}", self.samples_per_pixel);
+                self.settings.samples_per_pixel = new_val as i16;
+                self.refine_submitted = false;
+            }
+            Msg::DenoiseChanged(selected) => {
+                // First entry of the dropdown disables denoising
+                self.denoise = selected
+                    .checked_sub(1)
+                    .and_then(|index| DenoiseMethod::ALL.get(index as usize).copied());
+                self.refine_submitted = false;
+            }
+            Msg::DragBegin(mode) => {
+                self.drag = Some(Drag {
+                    mode,
+                    last_offset: (0.0, 0.0),
+                });
+            }
+            Msg::DragUpdate(offset) => self.drag_to(offset),
+            Msg::DragEnd => {
+                self.drag = None;
+            }
+            Msg::Zoom(steps) => {
+                self.navigation.zoom(ZOOM_STEP.powf(steps as f32));
+                self.camera_changed();
+            }
+            Msg::KeyPressed(key, modifiers) => {
+                let key = key.to_lower();
+                if fly_direction(key).is_some() && !self.held_keys.contains(&key) {
+                    self.held_keys.push(key);
+                }
+                self.fast_fly = modifiers.contains(gdk::ModifierType::SHIFT_MASK);
+            }
+            Msg::KeyReleased(key) => {
+                let key = key.to_lower();
+                self.held_keys.retain(|held| *held != key);
+            }
+            Msg::Select(selection) => {
+                self.selection = selection;
+                self.show_properties(&sender);
             }
+            Msg::PropertyChanged(property, value) => {
+                let camera = &mut self.navigation.camera;
+                if outliner::apply(&mut self.scene, camera, self.selection, property, value) {
+                    self.show_properties(&sender);
+                }
+                self.camera_changed();
+            }
+        }
+    }
+
+    async fn update_cmd(
+        &mut self,
+        cmd: Self::CommandOutput,
+        _sender: AsyncComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match cmd {
+            Cmd::Tick => self.tick(),
+            Cmd::Rendered(update) if Some(update.job) == self.job => match update.result {
+                Ok(frame) => self.frame_view.show(&frame),
+                Err(err) => eprintln!("Render failed: {}", err),
+            },
+            // Frame of a replaced job
+            Cmd::Rendered(_) => {}
         }
     }
 
@@ -110,22 +278,51 @@ impl AsyncComponent for App {
         root: Self::Root,
         sender: AsyncComponentSender<Self>,
     ) -> AsyncComponentParts<Self> {
+        let frame_view = FrameView::new();
+        let scale_factor = frame_view.scale_factor();
+        let scene = SceneDescription::default();
+        let outliner = Outliner::new(&scene, &sender);
         let model = App {
-            width: 100,
-            height: 100,
-            handler: DrawHandler::new(),
-            samples_per_pixel: DEFAULT_SAMPLES_PER_PIXEL_VALUE,
+            frame_view,
+            settings: RenderSettings {
+                width: 100,
+                height: 100,
+                samples_per_pixel: DEFAULT_SAMPLES_PER_PIXEL_VALUE,
+                ..Default::default()
+            },
+            scale_factor,
+            denoise: None,
+            navigation: Navigation::new(CameraDescription::default()),
+            scene,
+            outliner,
+            selection: Selection::Camera,
+            drag: None,
+            held_keys: vec![],
+            fast_fly: false,
+            last_tick: Instant::now(),
+            last_camera_change: Instant::now(),
+            preview_pending: true,
+            refine_submitted: false,
+            worker: RenderWorker::new(),
+            job: None,
         };
 
-        let area = model.handler.drawing_area();
+        let area = model.frame_view.drawing_area();
+        let outliner_panel = model.outliner.widget();
         let widgets = view_output!();
+        model.show_properties(&sender);
+        add_navigation_controllers(area, &sender);
+
+        let worker = model.worker.clone();
+        sender.command(move |out, shutdown| {
+            let frames = out.clone();
+            worker.start(move |update| frames.send(Cmd::Rendered(update)).is_ok());
 
-        sender.command(|out, shutdown| {
             shutdown
                 .register(async move {
                     loop {
                         tokio::time::sleep(Duration::from_millis(20)).await;
-                        out.send(()).unwrap();
+                        out.send(Cmd::Tick).unwrap();
                     }
                 })
                 .drop_on_shutdown()
@@ -135,27 +332,185 @@ impl AsyncComponent for App {
     }
 }
 
-async fn render(width: i32, height: i32, samples_per_pixel: i16) -> shared::data::Frame {
-    let renderer = Renderer::default();
-    renderer.render(width, height, samples_per_pixel)
-}
+impl App {
+    fn show_properties(&self, sender: &AsyncComponentSender<Self>) {
+        let camera = &self.navigation.camera;
+        self.outliner
+            .show(self.selection, &self.scene, camera, sender);
+    }
 
-fn draw(cx: &Context, frame: shared::data::Frame) {
-    let frame_iter = frame.pixels.into_iter();
-    let mut y = frame.height - 1;
-    let mut x = 0;
+    /**
+     * Next tick queues a fast preview of the new view in place of the running render
+     */
+    fn camera_changed(&mut self) {
+        self.preview_pending = true;
+        self.last_camera_change = Instant::now();
+        self.refine_submitted = false;
+    }
 
-    for pixel in frame_iter {
-        cx.set_source_rgb(pixel.r, pixel.g, pixel.b);
-        cx.rectangle(x as f64, y as f64, 1.0, 1.0);
-        cx.fill().expect("Couldn't fill rect");
+    /**
+     * Runs every few milliseconds: moves the camera with held keys, then queues a preview
+     * after camera changes or the full quality render once the camera is still
+     */
+    fn tick(&mut self) {
+        let elapsed = self.last_tick.elapsed().as_secs_f32();
+        self.last_tick = Instant::now();
+        self.fly(elapsed);
 
-        x += 1;
-        if x == frame.width {
-            y -= 1;
-            x = 0;
+        if self.preview_pending {
+            self.preview_pending = false;
+            self.submit_preview();
+        } else if !self.refine_submitted && self.last_camera_change.elapsed() >= SETTLE_TIME {
+            self.refine_submitted = true;
+            self.submit_refinement();
         }
     }
+
+    fn drag_to(&mut self, offset: (f64, f64)) {
+        let Some(drag) = &mut self.drag else {
+            return;
+        };
+        let (dx, dy) = (offset.0 - drag.last_offset.0, offset.1 - drag.last_offset.1);
+        drag.last_offset = offset;
+
+        match drag.mode {
+            DragMode::Orbit => self
+                .navigation
+                .orbit((-dx * ORBIT_SPEED) as f32, (dy * ORBIT_SPEED) as f32),
+            DragMode::Pan => {
+                // Drag offsets are logical pixels
+                let height = (self.settings.height / self.scale_factor) as f64;
+                self.navigation
+                    .pan((dx / height) as f32, (dy / height) as f32)
+            }
+            DragMode::Look => self
+                .navigation
+                .look((-dx * LOOK_SPEED) as f32, (-dy * LOOK_SPEED) as f32),
+        }
+        self.camera_changed();
+    }
+
+    fn fly(&mut self, seconds: f32) {
+        if self.held_keys.is_empty() {
+            return;
+        }
+
+        let speed = match self.fast_fly {
+            true => FLY_SPEED * FAST_FLY_FACTOR,
+            false => FLY_SPEED,
+        };
+        let mut direction = [0.0; 3];
+        for key in &self.held_keys {
+            if let Some(key_direction) = fly_direction(*key) {
+                for (axis, value) in direction.iter_mut().zip(key_direction) {
+                    *axis += value * speed * seconds;
+                }
+            }
+        }
+
+        let [forward, right, up] = direction;
+        self.navigation.fly(forward, right, up);
+        self.camera_changed();
+    }
+
+    fn submit_preview(&mut self) {
+        let settings = RenderSettings {
+            width: (self.settings.width / PREVIEW_SCALE).max(2),
+            height: (self.settings.height / PREVIEW_SCALE).max(2),
+            samples_per_pixel: 1,
+            max_depth: self.settings.max_depth.min(PREVIEW_MAX_DEPTH),
+            ..self.settings.clone()
+        };
+        self.job = Some(self.worker.submit(RenderJob {
+            scene: self.scene.clone(),
+            camera: self.navigation.camera.clone(),
+            film: film(None),
+            settings,
+            pass_samples: 1,
+        }));
+    }
+
+    /**
+     * Worker refines the current view pass by pass and shows all samples so far after each
+     */
+    fn submit_refinement(&mut self) {
+        self.job = Some(self.worker.submit(RenderJob {
+            scene: self.scene.clone(),
+            camera: self.navigation.camera.clone(),
+            film: film(self.denoise),
+            settings: self.settings.clone(),
+            pass_samples: REFINE_SAMPLES,
+        }));
+    }
+}
+
+/**
+ * Mouse and keyboard controls moving the camera
+ */
+fn add_navigation_controllers(area: &gtk::DrawingArea, sender: &AsyncComponentSender<App>) {
+    let drag = gtk::GestureDrag::new();
+    drag.set_button(0);
+    let (drag_sender, drag_area) = (sender.clone(), area.clone());
+    drag.connect_drag_begin(move |gesture, _, _| {
+        drag_area.grab_focus();
+        let shift = gesture
+            .current_event_state()
+            .contains(gdk::ModifierType::SHIFT_MASK);
+        let mode = match gesture.current_button() {
+            2 => DragMode::Pan,
+            3 => DragMode::Look,
+            _ if shift => DragMode::Pan,
+            _ => DragMode::Orbit,
+        };
+        drag_sender.input(Msg::DragBegin(mode));
+    });
+    let update_sender = sender.clone();
+    drag.connect_drag_update(move |_, x, y| update_sender.input(Msg::DragUpdate((x, y))));
+    let end_sender = sender.clone();
+    drag.connect_drag_end(move |_, _, _| end_sender.input(Msg::DragEnd));
+    area.add_controller(&drag);
+
+    let scroll = gtk::EventControllerScroll::new(gtk::EventControllerScrollFlags::VERTICAL);
+    let scroll_sender = sender.clone();
+    scroll.connect_scroll(move |_, _, dy| {
+        scroll_sender.input(Msg::Zoom(dy));
+        gtk::Inhibit(true)
+    });
+    area.add_controller(&scroll);
+
+    let keys = gtk::EventControllerKey::new();
+    let press_sender = sender.clone();
+    keys.connect_key_pressed(move |_, key, _, modifiers| {
+        press_sender.input(Msg::KeyPressed(key, modifiers));
+        gtk::Inhibit(fly_direction(key.to_lower()).is_some())
+    });
+    let release_sender = sender.clone();
+    keys.connect_key_released(move |_, key, _, _| release_sender.input(Msg::KeyReleased(key)));
+    area.add_controller(&keys);
+}
+
+/**
+ * Forward, right and up movement of a fly key
+ */
+fn fly_direction(key: gdk::Key) -> Option<[f32; 3]> {
+    let keys = [
+        (gdk::Key::w, [1.0, 0.0, 0.0]),
+        (gdk::Key::s, [-1.0, 0.0, 0.0]),
+        (gdk::Key::d, [0.0, 1.0, 0.0]),
+        (gdk::Key::a, [0.0, -1.0, 0.0]),
+        (gdk::Key::e, [0.0, 0.0, 1.0]),
+        (gdk::Key::q, [0.0, 0.0, -1.0]),
+    ];
+    keys.into_iter()
+        .find(|(fly_key, _)| *fly_key == key)
+        .map(|(_, direction)| direction)
+}
+
+fn film(denoise: Option<DenoiseMethod>) -> FilmSettings {
+    FilmSettings {
+        denoise,
+        ..Default::default()
+    }
 }
 
 fn main() {
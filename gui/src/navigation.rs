@@ -0,0 +1,134 @@
+use renderer::{math::vec3::Vec3, CameraDescription};
+
+/**
+ * Closest the camera may get to the point it orbits
+ */
+const MIN_DISTANCE: f32 = 0.05;
+
+/**
+ * Smallest angle between view direction and up, keeps the camera from flipping over the pole
+ */
+const MIN_POLAR_ANGLE: f32 = 0.01;
+
+/**
+ * Moves a camera description like a 3D viewport. Orbit, pan and zoom move around the point
+ * looked at, look and fly move the camera itself. Angles are in radians.
+ */
+pub struct Navigation {
+    pub camera: CameraDescription,
+}
+
+impl Navigation {
+    pub fn new(camera: CameraDescription) -> Self {
+        Self { camera }
+    }
+
+    fn look_from(&self) -> Vec3 {
+        Vec3::new(self.camera.look_from)
+    }
+
+    fn look_at(&self) -> Vec3 {
+        Vec3::new(self.camera.look_at)
+    }
+
+    fn world_up(&self) -> Vec3 {
+        Vec3::new(self.camera.up).unit_vector()
+    }
+
+    fn set(&mut self, look_from: Vec3, look_at: Vec3) {
+        self.camera.look_from = [look_from.x(), look_from.y(), look_from.z()];
+        self.camera.look_at = [look_at.x(), look_at.y(), look_at.z()];
+    }
+
+    /**
+     * View direction, right and up of the image as unit vectors
+     */
+    fn basis(&self) -> (Vec3, Vec3, Vec3) {
+        let forward = (self.look_at() - self.look_from()).unit_vector();
+        let right = forward.cross(&self.world_up()).unit_vector();
+        (forward, right, right.cross(&forward))
+    }
+
+    pub fn distance(&self) -> f32 {
+        (self.look_at() - self.look_from()).length()
+    }
+
+    /**
+     * Height of the visible area at the distance of the point looked at
+     */
+    fn view_height(&self) -> f32 {
+        2.0 * self.distance() * (self.camera.fov.to_radians() / 2.0).tan()
+    }
+
+    /**
+     * Turns camera around the point looked at. Positive yaw moves it right, positive pitch up.
+     */
+    pub fn orbit(&mut self, yaw: f32, pitch: f32) {
+        let look_at = self.look_at();
+        let (_, right, _) = self.basis();
+        let offset = rotate(&(self.look_from() - look_at), &self.world_up(), yaw);
+        let offset = rotate(&offset, &right, self.clamped_pitch(&offset, pitch));
+        self.set(look_at + offset, look_at);
+    }
+
+    /**
+     * Moves camera and point looked at sideways, offsets are fractions of the view height
+     */
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        let (_, right, up) = self.basis();
+        let view_height = self.view_height();
+        let shift = right * (-dx * view_height) + up * (dy * view_height);
+        self.set(self.look_from() + shift, self.look_at() + shift);
+    }
+
+    /**
+     * Scales distance to the point looked at, factors below 1 move closer
+     */
+    pub fn zoom(&mut self, factor: f32) {
+        let look_at = self.look_at();
+        let offset = self.look_from() - look_at;
+        let distance = (offset.length() * factor).max(MIN_DISTANCE);
+        self.set(look_at + offset.unit_vector() * distance, look_at);
+    }
+
+    /**
+     * Turns camera in place like a first person view. Positive yaw looks left, positive pitch up.
+     */
+    pub fn look(&mut self, yaw: f32, pitch: f32) {
+        let look_from = self.look_from();
+        let (_, right, _) = self.basis();
+        let direction = rotate(&(self.look_at() - look_from), &self.world_up(), yaw);
+        // Rotating the view direction up lowers its angle to world up
+        let direction = rotate(&direction, &right, -self.clamped_pitch(&direction, pitch));
+        self.set(look_from, look_from + direction);
+    }
+
+    /**
+     * Moves camera and point looked at along the view direction, right and up
+     */
+    pub fn fly(&mut self, forward: f32, right: f32, up: f32) {
+        let (forward_axis, right_axis, _) = self.basis();
+        let shift = forward_axis * forward + right_axis * right + self.world_up() * up;
+        self.set(self.look_from() + shift, self.look_at() + shift);
+    }
+
+    /**
+     * Change of the angle between vector and world up when raising it by pitch, stopping
+     * short of the poles. Rotating the orbit offset around image right by it applies it.
+     */
+    fn clamped_pitch(&self, vector: &Vec3, pitch: f32) -> f32 {
+        let polar = (vector.unit_vector().dot(&self.world_up()))
+            .clamp(-1.0, 1.0)
+            .acos();
+        let max_angle = std::f32::consts::PI - MIN_POLAR_ANGLE;
+        (polar - pitch).clamp(MIN_POLAR_ANGLE, max_angle) - polar
+    }
+}
+
+/**
+ * Rodrigues rotation of vector around unit axis
+ */
+fn rotate(vector: &Vec3, axis: &Vec3, angle: f32) -> Vec3 {
+    let (sin, cos) = angle.sin_cos();
+    vector * cos + axis.cross(vector) * sin + axis * (axis.dot(vector) * (1.0 - cos))
+}
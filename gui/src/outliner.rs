@@ -0,0 +1,335 @@
+use gtk::prelude::*;
+use relm4::{gtk, AsyncComponentSender};
+use renderer::{
+    scene::{material_description::MaterialDescription, scene_description::SceneDescription},
+    CameraDescription,
+};
+
+use crate::{App, Msg};
+
+/**
+ * Material kinds that can be picked in the property editor
+ */
+const MATERIAL_KINDS: [&str; 3] = ["Lambertian", "Metal", "Dielectric"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Selection {
+    Camera,
+    Sphere(usize),
+    Material(usize),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Property {
+    Fov,
+    Aperture,
+    /// Axis of the sphere center
+    Center(usize),
+    Radius,
+    /// Index of the material used by the sphere
+    SphereMaterial,
+    /// Index in MATERIAL_KINDS
+    MaterialKind,
+    /// Color channel
+    Albedo(usize),
+    Fuzziness,
+    RefractionIndex,
+}
+
+/**
+ * Index of the kind in MATERIAL_KINDS, None for kinds the editor doesn't offer
+ */
+fn material_kind(material: &MaterialDescription) -> Option<usize> {
+    match material {
+        MaterialDescription::Lambertian { .. } => Some(0),
+        MaterialDescription::Metal { .. } => Some(1),
+        MaterialDescription::Dielectric { .. } => Some(2),
+        _ => None,
+    }
+}
+
+/**
+ * Material of another kind, keeping the albedo when both kinds have one
+ */
+fn with_kind(material: &MaterialDescription, kind: usize) -> MaterialDescription {
+    let albedo = match *material {
+        MaterialDescription::Lambertian { albedo } | MaterialDescription::Metal { albedo, .. } => {
+            albedo
+        }
+        _ => [0.8, 0.8, 0.8],
+    };
+
+    match kind {
+        0 => MaterialDescription::Lambertian { albedo },
+        1 => MaterialDescription::Metal {
+            albedo,
+            fuzziness: 0.3,
+        },
+        _ => MaterialDescription::Dielectric {
+            refraction_index: 1.5,
+        },
+    }
+}
+
+/**
+ * Sets property of the selected item. Returns true when the item got other properties and
+ * the editor has to be rebuilt.
+ */
+pub fn apply(
+    scene: &mut SceneDescription,
+    camera: &mut CameraDescription,
+    selection: Selection,
+    property: Property,
+    value: f64,
+) -> bool {
+    let value = value as f32;
+    match (selection, property) {
+        (Selection::Camera, Property::Fov) => camera.fov = value,
+        (Selection::Camera, Property::Aperture) => camera.aperture = value,
+        (Selection::Sphere(index), Property::Center(axis)) => {
+            scene.spheres[index].center[axis] = value
+        }
+        (Selection::Sphere(index), Property::Radius) => scene.spheres[index].radius = value,
+        (Selection::Sphere(index), Property::SphereMaterial) => {
+            scene.spheres[index].material = value as usize
+        }
+        (Selection::Material(index), Property::MaterialKind) => {
+            let material = &mut scene.materials[index].material;
+            *material = with_kind(material, value as usize);
+            return true;
+        }
+        (Selection::Material(index), property) => {
+            set_material_property(&mut scene.materials[index].material, property, value)
+        }
+        _ => {}
+    }
+    false
+}
+
+fn set_material_property(material: &mut MaterialDescription, property: Property, value: f32) {
+    match (material, property) {
+        (
+            MaterialDescription::Lambertian { albedo } | MaterialDescription::Metal { albedo, .. },
+            Property::Albedo(channel),
+        ) => albedo[channel] = value,
+        (MaterialDescription::Metal { fuzziness, .. }, Property::Fuzziness) => *fuzziness = value,
+        (MaterialDescription::Dielectric { refraction_index }, Property::RefractionIndex) => {
+            *refraction_index = value
+        }
+        _ => {}
+    }
+}
+
+/**
+ * Side panel listing camera, objects and materials of the scene with a property editor for
+ * the selected one
+ */
+pub struct Outliner {
+    root: gtk::Box,
+    properties: gtk::Box,
+}
+
+impl Outliner {
+    pub fn new(scene: &SceneDescription, sender: &AsyncComponentSender<App>) -> Self {
+        let mut entries = vec![("Camera".to_string(), Some(Selection::Camera))];
+        entries.push(("Objects".to_string(), None));
+        for (index, sphere) in scene.spheres.iter().enumerate() {
+            entries.push((sphere.name.clone(), Some(Selection::Sphere(index))));
+        }
+        entries.push(("Materials".to_string(), None));
+        for (index, material) in scene.materials.iter().enumerate() {
+            entries.push((material.name.clone(), Some(Selection::Material(index))));
+        }
+
+        let list = gtk::ListBox::new();
+        for (name, selection) in &entries {
+            let label = gtk::Label::new(Some(name));
+            label.set_xalign(0.0);
+            let row = gtk::ListBoxRow::new();
+            row.set_child(Some(&label));
+            match selection {
+                // Headings group the items below them
+                None => {
+                    label.add_css_class("heading");
+                    row.set_selectable(false);
+                    row.set_activatable(false);
+                }
+                Some(_) => label.set_margin_start(12),
+            }
+            list.append(&row);
+        }
+        list.select_row(list.row_at_index(0).as_ref());
+
+        let selections: Vec<Option<Selection>> = entries.into_iter().map(|(_, s)| s).collect();
+        let select_sender = sender.clone();
+        list.connect_row_selected(move |_, row| {
+            if let Some(selection) = row.and_then(|row| selections[row.index() as usize]) {
+                select_sender.input(Msg::Select(selection));
+            }
+        });
+
+        let properties = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        let root = gtk::Box::new(gtk::Orientation::Vertical, 10);
+        root.set_width_request(260);
+        root.append(&list);
+        root.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+        root.append(&properties);
+
+        Self { root, properties }
+    }
+
+    pub fn widget(&self) -> &gtk::Box {
+        &self.root
+    }
+
+    /**
+     * Replaces the property editor with one for selection
+     */
+    pub fn show(
+        &self,
+        selection: Selection,
+        scene: &SceneDescription,
+        camera: &CameraDescription,
+        sender: &AsyncComponentSender<App>,
+    ) {
+        while let Some(child) = self.properties.first_child() {
+            self.properties.remove(&child);
+        }
+        let mut editor = PropertyEditor::new(sender);
+
+        match selection {
+            Selection::Camera => {
+                editor.number(
+                    "Field of view",
+                    Property::Fov,
+                    camera.fov,
+                    (1.0, 179.0, 1.0),
+                );
+                editor.number(
+                    "Aperture",
+                    Property::Aperture,
+                    camera.aperture,
+                    (0.0, 10.0, 0.05),
+                );
+            }
+            Selection::Sphere(index) => {
+                let sphere = &scene.spheres[index];
+                for (axis, label) in ["Center X", "Center Y", "Center Z"].iter().enumerate() {
+                    editor.number(
+                        label,
+                        Property::Center(axis),
+                        sphere.center[axis],
+                        (-1000.0, 1000.0, 0.1),
+                    );
+                }
+                editor.number(
+                    "Radius",
+                    Property::Radius,
+                    sphere.radius,
+                    (0.01, 1000.0, 0.05),
+                );
+                let names: Vec<&str> = scene.materials.iter().map(|m| m.name.as_str()).collect();
+                editor.choice(
+                    "Material",
+                    Property::SphereMaterial,
+                    &names,
+                    sphere.material,
+                );
+            }
+            Selection::Material(index) => {
+                let material = &scene.materials[index].material;
+                if let Some(kind) = material_kind(material) {
+                    editor.choice("Type", Property::MaterialKind, &MATERIAL_KINDS, kind);
+                }
+                match *material {
+                    MaterialDescription::Lambertian { albedo } => editor.albedo(albedo),
+                    MaterialDescription::Metal { albedo, fuzziness } => {
+                        editor.albedo(albedo);
+                        editor.number(
+                            "Fuzziness",
+                            Property::Fuzziness,
+                            fuzziness,
+                            (0.0, 1.0, 0.05),
+                        );
+                    }
+                    MaterialDescription::Dielectric { refraction_index } => editor.number(
+                        "Refraction index",
+                        Property::RefractionIndex,
+                        refraction_index,
+                        (1.0, 3.0, 0.01),
+                    ),
+                    _ => {}
+                }
+            }
+        }
+
+        self.properties.append(&editor.grid);
+    }
+}
+
+/**
+ * Grid of labeled fields, each change is sent as Msg::PropertyChanged
+ */
+struct PropertyEditor {
+    grid: gtk::Grid,
+    rows: i32,
+    sender: AsyncComponentSender<App>,
+}
+
+impl PropertyEditor {
+    fn new(sender: &AsyncComponentSender<App>) -> Self {
+        let grid = gtk::Grid::new();
+        grid.set_row_spacing(6);
+        grid.set_column_spacing(10);
+        Self {
+            grid,
+            rows: 0,
+            sender: sender.clone(),
+        }
+    }
+
+    fn add(&mut self, label: &str, field: &impl IsA<gtk::Widget>) {
+        let label = gtk::Label::new(Some(label));
+        label.set_xalign(0.0);
+        field.set_hexpand(true);
+        self.grid.attach(&label, 0, self.rows, 1, 1);
+        self.grid.attach(field, 1, self.rows, 1, 1);
+        self.rows += 1;
+    }
+
+    /**
+     * Spin button with minimum, maximum and step
+     */
+    fn number(&mut self, label: &str, property: Property, value: f32, range: (f64, f64, f64)) {
+        let (min, max, step) = range;
+        let spin = gtk::SpinButton::with_range(min, max, step);
+        spin.set_digits(2);
+        spin.set_value(value as f64);
+        let sender = self.sender.clone();
+        spin.connect_value_changed(move |spin| {
+            sender.input(Msg::PropertyChanged(property, spin.value()));
+        });
+        self.add(label, &spin);
+    }
+
+    fn choice(&mut self, label: &str, property: Property, options: &[&str], selected: usize) {
+        let dropdown = gtk::DropDown::from_strings(options);
+        dropdown.set_selected(selected as u32);
+        let sender = self.sender.clone();
+        dropdown.connect_selected_notify(move |dropdown| {
+            sender.input(Msg::PropertyChanged(property, dropdown.selected() as f64));
+        });
+        self.add(label, &dropdown);
+    }
+
+    fn albedo(&mut self, albedo: [f32; 3]) {
+        for (channel, label) in ["Albedo R", "Albedo G", "Albedo B"].iter().enumerate() {
+            self.number(
+                label,
+                Property::Albedo(channel),
+                albedo[channel],
+                (0.0, 1.0, 0.05),
+            );
+        }
+    }
+}
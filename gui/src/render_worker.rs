@@ -0,0 +1,158 @@
+use std::{
+    fmt,
+    sync::{Arc, Condvar, Mutex},
+    thread,
+};
+
+use renderer::{
+    scene::scene_description::SceneDescription, CameraDescription, Checkpoint, FilmSettings,
+    RenderError, RenderSettings, Renderer,
+};
+use shared::data::Frame;
+
+/**
+ * View to render. The samples of settings are added in passes of pass_samples and a
+ * frame is sent after each pass.
+ */
+pub struct RenderJob {
+    pub scene: SceneDescription,
+    pub camera: CameraDescription,
+    pub film: FilmSettings,
+    pub settings: RenderSettings,
+    pub pass_samples: i16,
+}
+
+/**
+ * Frame of a job after one of its passes
+ */
+pub struct RenderUpdate {
+    /// Id returned by RenderWorker::submit
+    pub job: u64,
+    pub result: Result<Frame, RenderError>,
+    /// Samples per pixel accumulated in frame
+    pub samples: i16,
+}
+
+impl fmt::Debug for RenderUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RenderUpdate")
+            .field("job", &self.job)
+            .field("ok", &self.result.is_ok())
+            .field("samples", &self.samples)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+struct Queue {
+    /// Job waiting for the worker, a newer submit replaces it
+    next: Option<(u64, RenderJob)>,
+    latest: u64,
+}
+
+/**
+ * Renders jobs one at a time on a background thread with its own thread pool, so neither
+ * the UI nor its async runtime waits for rendering. Submitting a job replaces the one
+ * waiting and stops the running job after its current pass.
+ */
+#[derive(Clone, Default)]
+pub struct RenderWorker {
+    queue: Arc<(Mutex<Queue>, Condvar)>,
+}
+
+impl RenderWorker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * Starts the render thread. Updates are passed to send until it returns false.
+     */
+    pub fn start<F>(&self, send: F)
+    where
+        F: Fn(RenderUpdate) -> bool + Send + 'static,
+    {
+        let worker = self.clone();
+        thread::Builder::new()
+            .name("render-worker".to_string())
+            .spawn(move || worker.run(send))
+            .expect("Couldn't start render thread");
+    }
+
+    /**
+     * Queues job in place of any waiting or running one and returns its id
+     */
+    pub fn submit(&self, job: RenderJob) -> u64 {
+        let (queue, wake) = &*self.queue;
+        let mut queue = queue.lock().unwrap();
+        queue.latest += 1;
+        let id = queue.latest;
+        queue.next = Some((id, job));
+        wake.notify_one();
+        id
+    }
+
+    fn next_job(&self) -> (u64, RenderJob) {
+        let (queue, wake) = &*self.queue;
+        let mut queue = queue.lock().unwrap();
+        loop {
+            if let Some(job) = queue.next.take() {
+                return job;
+            }
+            queue = wake.wait(queue).unwrap();
+        }
+    }
+
+    fn is_replaced(&self, id: u64) -> bool {
+        self.queue.0.lock().unwrap().latest != id
+    }
+
+    fn run<F>(&self, send: F)
+    where
+        F: Fn(RenderUpdate) -> bool,
+    {
+        // One core is left to the UI
+        let threads = thread::available_parallelism().map_or(1, |cores| cores.get().max(2) - 1);
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|index| format!("render-{}", index))
+            .build()
+            .expect("Couldn't create render thread pool");
+
+        loop {
+            let (id, job) = self.next_job();
+            let renderer = Renderer::new(job.scene.build(), job.film).with_camera(job.camera);
+            let mut checkpoint: Option<Checkpoint> = None;
+            let mut samples = 0;
+
+            while samples < job.settings.samples_per_pixel && !self.is_replaced(id) {
+                let settings = RenderSettings {
+                    samples_per_pixel: (job.settings.samples_per_pixel - samples)
+                        .min(job.pass_samples.max(1)),
+                    ..job.settings.clone()
+                };
+                let result = pool.install(|| renderer.render_resumed(&settings, checkpoint.take()));
+
+                let result = match result {
+                    Ok((frame, resumed)) => {
+                        checkpoint = Some(resumed);
+                        samples += settings.samples_per_pixel;
+                        Ok(frame)
+                    }
+                    // Finish the job, retrying would fail the same way
+                    Err(err) => {
+                        samples = job.settings.samples_per_pixel;
+                        Err(err)
+                    }
+                };
+                if !send(RenderUpdate {
+                    job: id,
+                    result,
+                    samples,
+                }) {
+                    return;
+                }
+            }
+        }
+    }
+}
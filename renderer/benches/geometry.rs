@@ -0,0 +1,137 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use renderer::{
+    materials::{lambertian::Lambertian, material::MaterialType},
+    math::vec3::Vec3,
+    scene::scene::Scene,
+    shapes::{sphere::Sphere, traits::Hit, triangle::Triangle},
+    util::{color::Color, ray::Ray},
+};
+
+/**
+ * Inputs are generated from fixed seeds so every commit benchmarks the same work
+ */
+const SEED: u64 = 0x5eed;
+const BATCH: usize = 1024;
+
+fn random_vectors(rng: &mut StdRng, count: usize) -> Vec<Vec3> {
+    (0..count)
+        .map(|_| Vec3::new([(); 3].map(|_| rng.gen_range(-1.0..1.0))))
+        .collect()
+}
+
+/**
+ * Rays from a box around the origin towards random points near the origin, roughly half
+ * of them hit the unit sphere
+ */
+fn random_rays(rng: &mut StdRng, count: usize) -> Vec<Ray> {
+    (0..count)
+        .map(|_| {
+            let origin = Vec3::new([(); 3].map(|_| rng.gen_range(-5.0..5.0)));
+            let target = Vec3::new([(); 3].map(|_| rng.gen_range(-1.5..1.5)));
+            Ray::new(origin, target - origin)
+        })
+        .collect()
+}
+
+fn gray() -> MaterialType {
+    MaterialType::Lambertian(Lambertian {
+        albedo: Color::new([0.5, 0.5, 0.5]),
+    })
+}
+
+fn vec3_operators(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let a = random_vectors(&mut rng, BATCH);
+    let b = random_vectors(&mut rng, BATCH);
+
+    let mut group = c.benchmark_group("vec3");
+    group.throughput(Throughput::Elements(BATCH as u64));
+    group.bench_function("add_sub_scale", |bench| {
+        bench.iter(|| {
+            a.iter()
+                .zip(&b)
+                .fold(Vec3::default(), |sum, (a, b)| sum + (a - b) * 0.5)
+        })
+    });
+    group.bench_function("dot", |bench| {
+        bench.iter(|| a.iter().zip(&b).map(|(a, b)| a.dot(b)).sum::<f32>())
+    });
+    group.bench_function("cross", |bench| {
+        bench.iter(|| {
+            a.iter()
+                .zip(&b)
+                .fold(Vec3::default(), |sum, (a, b)| sum + a.cross(b))
+        })
+    });
+    group.bench_function("unit_vector", |bench| {
+        bench.iter(|| {
+            a.iter()
+                .fold(Vec3::default(), |sum, a| sum + a.unit_vector())
+        })
+    });
+    group.finish();
+}
+
+fn shape_intersection(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let rays = random_rays(&mut rng, BATCH);
+    let sphere = Sphere::new(Vec3::default(), 1.0, gray());
+    let triangle = Triangle::new(
+        [
+            Vec3::new([-1.0, -1.0, 0.0]),
+            Vec3::new([1.0, -1.0, 0.0]),
+            Vec3::new([0.0, 1.0, 0.0]),
+        ],
+        [[0.0, 0.0], [1.0, 0.0], [0.5, 1.0]],
+        gray(),
+    );
+
+    let mut group = c.benchmark_group("intersection");
+    group.throughput(Throughput::Elements(BATCH as u64));
+    group.bench_function("sphere", |bench| {
+        bench.iter(|| {
+            rays.iter()
+                .filter(|ray| sphere.hit(black_box(ray), 0.001, f32::MAX).is_some())
+                .count()
+        })
+    });
+    group.bench_function("triangle", |bench| {
+        bench.iter(|| {
+            rays.iter()
+                .filter(|ray| triangle.hit(black_box(ray), 0.001, f32::MAX).is_some())
+                .count()
+        })
+    });
+    group.finish();
+}
+
+fn collection_intersection(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let rays = random_rays(&mut rng, BATCH);
+    let scenes = [
+        ("default_scene", Scene::default()),
+        ("random_scene", Scene::random(SEED)),
+    ];
+
+    let mut group = c.benchmark_group("hit_collection");
+    group.throughput(Throughput::Elements(BATCH as u64));
+    for (name, scene) in &scenes {
+        group.bench_function(*name, |bench| {
+            bench.iter(|| {
+                rays.iter()
+                    .filter(|ray| scene.world.hit(black_box(ray), 0.001, f32::MAX).is_some())
+                    .count()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    vec3_operators,
+    shape_intersection,
+    collection_intersection
+);
+criterion_main!(benches);
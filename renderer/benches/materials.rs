@@ -0,0 +1,69 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use renderer::{
+    materials::{
+        conductor::Conductor,
+        dielectric::Dielectric,
+        lambertian::Lambertian,
+        material::{Material, MaterialType},
+        metal::Metal,
+        rough_dielectric::RoughDielectric,
+    },
+    math::vec3::Vec3,
+    shapes::{sphere::Sphere, traits::Hit},
+    util::{color::Color, ray::Ray},
+};
+
+fn materials() -> Vec<(&'static str, MaterialType)> {
+    vec![
+        (
+            "lambertian",
+            MaterialType::Lambertian(Lambertian {
+                albedo: Color::new([0.7, 0.3, 0.3]),
+            }),
+        ),
+        (
+            "metal",
+            MaterialType::Metal(Metal {
+                albedo: Color::new([0.8, 0.6, 0.2]),
+                fuzziness: 0.3,
+            }),
+        ),
+        (
+            "dielectric",
+            MaterialType::Dielectric(Dielectric {
+                refraction_index: 1.5,
+                dispersion: None,
+            }),
+        ),
+        ("conductor", MaterialType::Conductor(Conductor::gold(0.3))),
+        (
+            "rough_dielectric",
+            MaterialType::RoughDielectric(RoughDielectric {
+                refraction_index: 1.5,
+                roughness: 0.3,
+            }),
+        ),
+        ("principled", MaterialType::Principled(Box::default())),
+    ]
+}
+
+/**
+ * Scatters a ray hitting a unit sphere at an oblique angle. Scattering is random so each
+ * iteration samples a new direction from the same hit.
+ */
+fn scatter(c: &mut Criterion) {
+    let ray = Ray::new(Vec3::new([0.3, 0.4, 5.0]), Vec3::new([0.0, 0.0, -1.0]));
+
+    let mut group = c.benchmark_group("scatter");
+    for (name, material) in materials() {
+        let sphere = Sphere::new(Vec3::default(), 1.0, material);
+        let hit = sphere.hit(&ray, 0.001, f32::MAX).unwrap();
+        group.bench_function(name, |bench| {
+            bench.iter(|| hit.material.scatter(black_box(&ray), black_box(&hit)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, scatter);
+criterion_main!(benches);
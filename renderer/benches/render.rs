@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use renderer::{Render, RenderSettings, Renderer, Scene};
+
+const SEED: u64 = 0x5eed;
+const WIDTH: i32 = 160;
+const HEIGHT: i32 = 90;
+const SAMPLES_PER_PIXEL: i16 = 8;
+
+/**
+ * Whole frames at a small fixed size. Rendering is parallel, results depend on the number
+ * of cores so compare baselines from the same machine only.
+ */
+fn render(c: &mut Criterion) {
+    let mut group = c.benchmark_group("render");
+    group
+        .sample_size(10)
+        .measurement_time(Duration::from_secs(20));
+
+    let settings = RenderSettings {
+        width: WIDTH,
+        height: HEIGHT,
+        samples_per_pixel: SAMPLES_PER_PIXEL,
+        ..Default::default()
+    };
+
+    let renderer = Renderer::default();
+    group.bench_function("default_scene", |bench| {
+        bench.iter(|| renderer.render(&settings).unwrap())
+    });
+
+    let renderer = Renderer::default().with_scene(Scene::random(SEED));
+    group.bench_function("random_scene", |bench| {
+        bench.iter(|| renderer.render(&settings).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, render);
+criterion_main!(benches);
@@ -1,6 +1,18 @@
-mod materials;
-mod math;
+pub mod materials;
+pub mod math;
 pub mod renderer;
-mod scene;
-mod shapes;
-mod util;
+pub mod scene;
+pub mod shapes;
+mod spectral;
+pub mod textures;
+pub mod util;
+
+pub use crate::renderer::{checkpoint::Checkpoint, renderer::Renderer, settings::FilmSettings};
+pub use crate::scene::{
+    builder::SceneBuilder, camera_description::CameraDescription, scene::Scene,
+};
+pub use shared::{
+    error::RenderError,
+    settings::{CropOutput, CropWindow, Integrator, RenderSettings},
+    traits::Render,
+};
@@ -1,14 +1,24 @@
-mod materials;
-mod math;
-mod renderer;
-mod scene;
-mod shapes;
-mod util;
-use std::{fs::File, io::Write};
-
-use ::renderer::renderer::renderer::Renderer;
-use clap::{command, Parser};
-use shared::traits::Render;
+mod output;
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    process,
+    str::FromStr,
+};
+
+use clap::{Parser, ValueEnum};
+use output::y4m::Y4mWriter;
+use renderer::{
+    renderer::{aov::AovType, denoise::DenoiseMethod, filter::FilterType, stats::RenderStats},
+    scene::{
+        camera_description::{ApertureDescription, Projection},
+        scene_file::SceneFile,
+    },
+    CameraDescription, Checkpoint, CropOutput, CropWindow, FilmSettings, Integrator, Render,
+    RenderSettings, Renderer, Scene,
+};
+use shared::data::Frame;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -16,57 +26,564 @@ struct Cli {
     #[arg(short, long)]
     filename: Option<String>,
 
-    #[arg(long, default_value_t = 400, value_parser = clap::value_parser!(i32).range(2..))]
-    width: i32,
+    /// Render settings JSON file, flags given on command line override it
+    #[arg(long)]
+    settings: Option<PathBuf>,
+
+    /// Write the effective render settings as JSON to this file
+    #[arg(long)]
+    save_settings: Option<PathBuf>,
+
+    /// Image width [default: 400]
+    #[arg(long, value_parser = clap::value_parser!(i32).range(2..))]
+    width: Option<i32>,
+
+    /// Image height [default: 225]
+    #[arg(long, value_parser = clap::value_parser!(i32).range(2..))]
+    height: Option<i32>,
+
+    /// [default: 10]
+    #[arg(short, long, value_parser = clap::value_parser!(i16).range(5..200))]
+    samples_per_pixel: Option<i16>,
+
+    /// Bounces before a path is terminated [default: 50]
+    #[arg(long, value_parser = clap::value_parser!(i16).range(1..))]
+    max_depth: Option<i16>,
+
+    /// Worker threads, defaults to every core
+    #[arg(long)]
+    threads: Option<usize>,
+
+    /// Trace only this rectangle of the frame, in pixels from the top left corner
+    #[arg(long, value_names = ["X", "Y", "WIDTH", "HEIGHT"], num_args = 4)]
+    crop: Option<Vec<i32>>,
+
+    /// Trace only this rectangle of the frame, as fractions of the frame size
+    #[arg(long, value_names = ["X", "Y", "WIDTH", "HEIGHT"], num_args = 4, conflicts_with = "crop")]
+    crop_normalized: Option<Vec<f32>>,
+
+    /// Write the full frame with pixels outside the crop window left empty instead of the
+    /// cropped image
+    #[arg(long)]
+    crop_full_frame: bool,
+
+    /// Continue the render stored in this file if it exists and save all samples to it
+    /// afterwards. Combined with a crop window only that area is refined.
+    #[arg(long, conflicts_with_all = ["frames", "stats", "stats_json"])]
+    checkpoint: Option<PathBuf>,
+
+    /// JSON scene file with named cameras
+    #[arg(long)]
+    scene: Option<PathBuf>,
+
+    /// Name of camera to render through, defaults to first camera of the scene
+    #[arg(long)]
+    camera: Option<String>,
+
+    /// Camera position, overrides the selected camera
+    #[arg(long, value_names = ["X", "Y", "Z"], num_args = 3, allow_negative_numbers = true)]
+    look_from: Option<Vec<f32>>,
+
+    /// Point the camera looks at, overrides the selected camera
+    #[arg(long, value_names = ["X", "Y", "Z"], num_args = 3, allow_negative_numbers = true)]
+    look_at: Option<Vec<f32>>,
+
+    /// Camera up direction, overrides the selected camera
+    #[arg(long, value_names = ["X", "Y", "Z"], num_args = 3, allow_negative_numbers = true)]
+    up: Option<Vec<f32>>,
+
+    /// Vertical field of view in degrees
+    #[arg(long)]
+    fov: Option<f32>,
+
+    /// Lens diameter, 0 disables defocus blur
+    #[arg(long)]
+    aperture: Option<f32>,
+
+    /// Distance to plane in focus, defaults to distance between look-from and look-at
+    #[arg(long)]
+    focus_distance: Option<f32>,
+
+    /// Lens focal length in millimeters. This and the following camera flags switch to a
+    /// physical camera, which starts at 50 mm, 36x24 mm sensor, f/2.8, 1/125 s and ISO 100.
+    #[arg(long)]
+    focal_length: Option<f32>,
+
+    /// Sensor size in millimeters
+    #[arg(long, value_names = ["WIDTH", "HEIGHT"], num_args = 2)]
+    sensor: Option<Vec<f32>>,
+
+    /// Aperture as f-number, also sets exposure
+    #[arg(long)]
+    f_stop: Option<f32>,
+
+    /// Exposure time in seconds
+    #[arg(long)]
+    shutter: Option<f32>,
 
-    #[arg(long, default_value_t = 225, value_parser = clap::value_parser!(i32).range(2..))]
-    height: i32,
+    #[arg(long)]
+    iso: Option<f32>,
+
+    /// Polygonal aperture with this many blades
+    #[arg(long, value_parser = clap::value_parser!(u32).range(3..))]
+    aperture_blades: Option<u32>,
+
+    /// Rotation of the polygonal aperture in degrees
+    #[arg(long, requires = "aperture_blades", allow_negative_numbers = true)]
+    aperture_rotation: Option<f32>,
+
+    /// PPM image used as aperture shape, brightness is the transmission
+    #[arg(long, conflicts_with = "aperture_blades")]
+    aperture_mask: Option<String>,
+
+    /// Cat-eye vignetting strength of the physical camera, 0 disables
+    #[arg(long)]
+    cat_eye: Option<f32>,
+
+    /// Trace sampled wavelengths instead of RGB. Needed for dispersion, slower and noisier.
+    #[arg(long)]
+    spectral: bool,
+
+    /// Extra render passes: depth, normal, albedo, object_id, material_id, uv, position, direct,
+    /// indirect, sample_count
+    #[arg(long, value_delimiter = ',')]
+    aovs: Vec<AovType>,
+
+    /// Pixel reconstruction filter: box, tent, gaussian, mitchell, lanczos
+    #[arg(long, default_value_t = FilterType::default())]
+    filter: FilterType,
+
+    /// Filter noise from the image using albedo, normal and depth as guides: bilateral, atrous
+    #[arg(long)]
+    denoise: Option<DenoiseMethod>,
+
+    /// How AOVs are written next to the image
+    #[arg(long, value_enum, default_value_t = AovFormat::Files)]
+    aov_format: AovFormat,
+
+    /// Render animation frames start..end (inclusive) as numbered images, e.g. out.0001.ppm
+    #[arg(long)]
+    frames: Option<FrameRange>,
+
+    /// Frames per second of the animation
+    #[arg(long, default_value_t = 24, value_parser = clap::value_parser!(u32).range(1..))]
+    fps: u32,
+
+    /// Also write the animation frames as an uncompressed Y4M video to this file
+    #[arg(long, requires = "frames")]
+    y4m: Option<String>,
+
+    /// Seed for random numbers, renders with the same seed and settings are identical
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Print ray counts and timings after rendering and write per pixel cost heatmap <image>.cost.ppm
+    #[arg(long)]
+    stats: bool,
+
+    /// Write render statistics as JSON to this file, implies --stats
+    #[arg(long)]
+    stats_json: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy)]
+struct FrameRange {
+    start: u32,
+    end: u32,
+}
+
+impl FromStr for FrameRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once("..")
+            .ok_or_else(|| format!("expected frame range start..end, got '{}'", s))?;
+        let parse = |frame: &str| {
+            frame
+                .trim()
+                .parse::<u32>()
+                .map_err(|err| format!("invalid frame number '{}': {}", frame, err))
+        };
+        let range = FrameRange {
+            start: parse(start)?,
+            end: parse(end)?,
+        };
+
+        match range.start <= range.end {
+            true => Ok(range),
+            false => Err(format!("frame range {} is empty", s)),
+        }
+    }
+}
 
-    #[arg(short, long, default_value_t = 10, value_parser = clap::value_parser!(i16).range(5..200))]
-    samples_per_pixel: i16,
+#[derive(Clone, Copy, ValueEnum)]
+enum AovFormat {
+    /// One PFM file per AOV, named <image>.<aov>.pfm
+    Files,
+    /// Single multi-layer OpenEXR file <image>.exr containing beauty and all AOVs
+    Exr,
 }
 
 fn main() {
     let cli = Cli::parse();
-    write_img(
-        cli.filename.unwrap_or("out.ppm".to_string()),
-        cli.width,
-        cli.height,
-        cli.samples_per_pixel,
+    let file = cli.filename.clone().unwrap_or("out.ppm".to_string());
+
+    let scene_file = exit_on_error(scene_file(&cli));
+    let camera = exit_on_error(camera(&cli, &scene_file.cameras));
+    let timeline = scene_file.animation;
+
+    let settings = exit_on_error(render_settings(&cli));
+    if let Some(path) = &cli.save_settings {
+        exit_on_error(settings.save(path).map_err(|err| err.to_string()));
+    }
+
+    let film = FilmSettings {
+        filter: cli.filter,
+        aovs: cli.aovs.clone(),
+        denoise: cli.denoise,
+    };
+    let mut renderer = exit_on_error(
+        Renderer::new(Scene::default(), film).with_object_animations(&timeline.objects),
+    );
+
+    let Some(frames) = cli.frames else {
+        println!(
+            "Rendering image to file {} with size {}:{}. Samples per pixel: {}",
+            file, settings.width, settings.height, settings.samples_per_pixel
+        );
+        renderer = renderer.with_camera(timeline.camera.apply(&camera, 0.0));
+        if let Some(path) = &cli.checkpoint {
+            let frame = render_checkpoint(&renderer, &settings, path);
+            write_outputs(&file, &frame, cli.aov_format);
+            return;
+        }
+        let (frame, stats) = render(&renderer, &settings, &cli);
+        write_outputs(&file, &frame, cli.aov_format);
+        if let Some(stats) = stats {
+            write_heatmap(&file, &stats, &frame);
+            report_stats(&cli, &stats);
+        }
+        return;
+    };
+
+    let (video_width, video_height) = settings.output_size();
+    let mut video = cli.y4m.as_ref().map(|video_file| {
+        let writer = Y4mWriter::create(video_file, video_width, video_height, cli.fps)
+            .map_err(|err| format!("could not create {}: {}", video_file, err));
+        (video_file, exit_on_error(writer))
+    });
+
+    let mut total_stats: Option<RenderStats> = None;
+    for frame_number in frames.start..=frames.end {
+        let time = frame_number as f32 / cli.fps as f32;
+        let frame_file = numbered_file(&file, frame_number);
+        println!(
+            "Rendering frame {} ({:.3} s) to file {} with size {}:{}. Samples per pixel: {}",
+            frame_number,
+            time,
+            frame_file,
+            settings.width,
+            settings.height,
+            settings.samples_per_pixel
+        );
+
+        renderer = renderer
+            .with_camera(timeline.camera.apply(&camera, time))
+            .with_time(time);
+        let (frame, stats) = render(&renderer, &settings, &cli);
+        write_outputs(&frame_file, &frame, cli.aov_format);
+
+        if let Some((video_file, writer)) = &mut video {
+            exit_on_error(
+                writer
+                    .write_frame(&frame)
+                    .map_err(|err| format!("could not write {}: {}", video_file, err)),
+            );
+        }
+
+        if let Some(stats) = stats {
+            write_heatmap(&frame_file, &stats, &frame);
+            total_stats
+                .get_or_insert_with(Default::default)
+                .merge(&stats);
+        }
+    }
+
+    if let Some(stats) = total_stats {
+        report_stats(&cli, &stats);
+    }
+}
+
+/**
+ * Renders frame, collecting statistics only when asked for since timing costs a little
+ */
+fn render(
+    renderer: &Renderer,
+    settings: &RenderSettings,
+    cli: &Cli,
+) -> (Frame, Option<RenderStats>) {
+    let result = match cli.stats || cli.stats_json.is_some() {
+        true => renderer
+            .render_with_stats(settings)
+            .map(|(frame, stats)| (frame, Some(stats))),
+        false => renderer.render(settings).map(|frame| (frame, None)),
+    };
+    exit_on_error(result.map_err(|err| err.to_string()))
+}
+
+/**
+ * Renders on top of the checkpoint file when there is one and saves the samples back to it
+ */
+fn render_checkpoint(renderer: &Renderer, settings: &RenderSettings, path: &Path) -> Frame {
+    let checkpoint = match path.exists() {
+        true => Some(exit_on_error(
+            Checkpoint::load(path).map_err(|err| err.to_string()),
+        )),
+        false => None,
+    };
+    if let Some(checkpoint) = &checkpoint {
+        println!(
+            "Resuming checkpoint {} after {} pass(es)",
+            path.display(),
+            checkpoint.passes()
+        );
+    }
+
+    let (frame, checkpoint) = exit_on_error(
+        renderer
+            .render_resumed(settings, checkpoint)
+            .map_err(|err| err.to_string()),
+    );
+    println!("Writing checkpoint to file {}", path.display());
+    exit_on_error(checkpoint.save(path).map_err(|err| err.to_string()));
+    frame
+}
+
+fn report_stats(cli: &Cli, stats: &RenderStats) {
+    println!("{}", stats);
+
+    if let Some(path) = &cli.stats_json {
+        let json = serde_json::to_string_pretty(&stats.report()).unwrap();
+        exit_on_error(
+            std::fs::write(path, json)
+                .map_err(|err| format!("could not write {}: {}", path.display(), err)),
+        );
+    }
+}
+
+fn write_heatmap(file: &str, stats: &RenderStats, frame: &Frame) {
+    let heatmap_file = format!("{}.cost.ppm", Path::new(file).with_extension("").display());
+    println!("Writing pixel cost heatmap to file {}", heatmap_file);
+    exit_on_error(
+        write_img(&heatmap_file, &stats.heatmap(frame.width, frame.height))
+            .map_err(|err| format!("could not write {}: {}", heatmap_file, err)),
     );
 }
 
-fn write_img(file: String, width: i32, height: i32, samples_per_pixel: i16) {
-    println!(
-        "Rendering image to file {} with size {}:{}. Samples per pixel: {}",
-        file, width, height, samples_per_pixel
+fn exit_on_error<T>(result: Result<T, String>) -> T {
+    result.unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    })
+}
+
+/**
+ * Settings file given on command line or defaults, overridden by flags
+ */
+fn render_settings(cli: &Cli) -> Result<RenderSettings, String> {
+    let mut settings = match &cli.settings {
+        Some(path) => RenderSettings::load(path).map_err(|err| err.to_string())?,
+        None => RenderSettings::default(),
+    };
+
+    settings.width = cli.width.unwrap_or(settings.width);
+    settings.height = cli.height.unwrap_or(settings.height);
+    settings.samples_per_pixel = cli.samples_per_pixel.unwrap_or(settings.samples_per_pixel);
+    settings.max_depth = cli.max_depth.unwrap_or(settings.max_depth);
+    settings.seed = cli.seed.or(settings.seed);
+    settings.threads = cli.threads.or(settings.threads);
+    if cli.spectral {
+        settings.integrator = Integrator::Spectral;
+    }
+    if let Some(&[x, y, width, height]) = cli.crop.as_deref() {
+        settings.crop = Some(CropWindow::Pixels {
+            x,
+            y,
+            width,
+            height,
+        });
+    }
+    if let Some(&[x, y, width, height]) = cli.crop_normalized.as_deref() {
+        settings.crop = Some(CropWindow::Normalized {
+            x,
+            y,
+            width,
+            height,
+        });
+    }
+    if cli.crop_full_frame {
+        settings.crop_output = CropOutput::FullFrame;
+    }
+
+    settings.validate().map_err(|err| err.to_string())?;
+    Ok(settings)
+}
+
+/**
+ * Applies physical camera flags, switching to a physical camera when the scene camera isn't one
+ */
+fn physical_camera(cli: &Cli, projection: &mut Projection) {
+    let flags_given = [
+        cli.focal_length,
+        cli.f_stop,
+        cli.shutter,
+        cli.iso,
+        cli.cat_eye,
+    ]
+    .iter()
+    .any(Option::is_some)
+        || cli.sensor.is_some()
+        || cli.aperture_blades.is_some()
+        || cli.aperture_mask.is_some();
+    if flags_given && !matches!(projection, Projection::Physical { .. }) {
+        *projection = Projection::physical();
+    }
+
+    let Projection::Physical {
+        focal_length_mm,
+        sensor_mm,
+        f_stop,
+        shutter_s,
+        iso,
+        aperture,
+        cat_eye,
+    } = projection
+    else {
+        return;
+    };
+    *focal_length_mm = cli.focal_length.unwrap_or(*focal_length_mm);
+    if let Some(sensor) = &cli.sensor {
+        *sensor_mm = [sensor[0], sensor[1]];
+    }
+    *f_stop = cli.f_stop.unwrap_or(*f_stop);
+    *shutter_s = cli.shutter.unwrap_or(*shutter_s);
+    *iso = cli.iso.unwrap_or(*iso);
+    *cat_eye = cli.cat_eye.unwrap_or(*cat_eye);
+    if let Some(blades) = cli.aperture_blades {
+        *aperture = ApertureDescription::Polygon {
+            blades,
+            rotation: cli.aperture_rotation.unwrap_or_default(),
+        };
+    }
+    if let Some(path) = &cli.aperture_mask {
+        *aperture = ApertureDescription::Mask { path: path.clone() };
+    }
+}
+
+/**
+ * Scene file given on command line, or built in camera presets without animation. Files
+ * without cameras use the presets too.
+ */
+fn scene_file(cli: &Cli) -> Result<SceneFile, String> {
+    let mut scene_file = match &cli.scene {
+        Some(path) => SceneFile::load(path)?,
+        None => SceneFile::default(),
+    };
+    if scene_file.cameras.is_empty() {
+        scene_file.cameras = Scene::default_cameras();
+    }
+    Ok(scene_file)
+}
+
+/**
+ * Camera picked by name with CLI overrides applied
+ */
+fn camera(cli: &Cli, cameras: &[CameraDescription]) -> Result<CameraDescription, String> {
+    let mut camera = CameraDescription::find(cameras, cli.camera.as_deref())?.clone();
+
+    let vector = |values: &Vec<f32>| [values[0], values[1], values[2]];
+    if let Some(look_from) = &cli.look_from {
+        camera.look_from = vector(look_from);
+    }
+    if let Some(look_at) = &cli.look_at {
+        camera.look_at = vector(look_at);
+    }
+    if let Some(up) = &cli.up {
+        camera.up = vector(up);
+    }
+    camera.fov = cli.fov.unwrap_or(camera.fov);
+    camera.aperture = cli.aperture.unwrap_or(camera.aperture);
+    camera.focus_distance = cli.focus_distance.or(camera.focus_distance);
+    physical_camera(cli, &mut camera.projection);
+    camera.validate().map_err(|err| err.to_string())?;
+
+    Ok(camera)
+}
+
+/**
+ * Inserts zero padded frame number before extension, out.ppm becomes out.0001.ppm
+ */
+fn numbered_file(file: &str, frame_number: u32) -> String {
+    let path = Path::new(file);
+    let stem = path.with_extension("");
+    match path.extension() {
+        Some(extension) => format!(
+            "{}.{:04}.{}",
+            stem.to_string_lossy(),
+            frame_number,
+            extension.to_string_lossy()
+        ),
+        None => format!("{}.{:04}", stem.to_string_lossy(), frame_number),
+    }
+}
+
+fn write_outputs(file: &str, frame: &Frame, aov_format: AovFormat) {
+    exit_on_error(
+        write_img(file, frame).map_err(|err| format!("could not write {}: {}", file, err)),
     );
-    let renderer = Renderer::default();
-    let frame = renderer.render(width, height, samples_per_pixel);
+    if !frame.aovs.is_empty() {
+        write_aovs(file, frame, aov_format);
+    }
+}
+
+fn write_img(file: &str, frame: &Frame) -> std::io::Result<()> {
+    let width = frame.width;
+    let height = frame.height;
+    // PPM has no alpha, background stays visible
     let frame_str = frame
-        .pixels
-        .chunks(width as usize) // Divide into rows
-        .into_iter()
-        .rev() // Go rows from bottom to top
-        .map(|row| {
-            // Each pixel of row to string format
-            row.into_iter()
-                .map(|pixel| {
-                    format!(
-                        "{} {} {}",
-                        (pixel.r * 256.0) as i16,
-                        (pixel.g * 256.0) as i16,
-                        (pixel.b * 256.0) as i16
-                    )
-                })
-                .collect::<Vec<String>>()
-                .join("\n")
-        })
+        .to_srgb8()
+        .iter()
+        .map(|pixel| format!("{} {} {}", pixel.r, pixel.g, pixel.b))
         .collect::<Vec<String>>()
-        .join("\n"); // Combine rows
+        .join("\n");
+
+    let mut out_file = File::create(file)?;
+    out_file.write_all(format!("P3\n{width} {height}\n255\n{frame_str}").as_bytes())
+}
+
+fn write_aovs(file: &str, frame: &Frame, format: AovFormat) {
+    let stem = Path::new(file).with_extension("");
+    let stem = stem.to_string_lossy();
 
-    let mut out_file = File::create(file).unwrap();
-    out_file
-        .write_all(format!("P3\n{width} {height}\n255\n{frame_str}").as_bytes())
-        .unwrap();
+    match format {
+        AovFormat::Files => {
+            for aov in &frame.aovs {
+                let aov_file = format!("{}.{}.pfm", stem, aov.name);
+                println!("Writing AOV {} to file {}", aov.name, aov_file);
+                exit_on_error(
+                    output::pfm::write_aov(&aov_file, aov, frame.width, frame.height)
+                        .map_err(|err| format!("could not write {}: {}", aov_file, err)),
+                );
+            }
+        }
+        AovFormat::Exr => {
+            let exr_file = format!("{}.exr", stem);
+            println!("Writing image and AOVs to file {}", exr_file);
+            exit_on_error(
+                output::exr::write_frame(&exr_file, frame)
+                    .map_err(|err| format!("could not write {}: {}", exr_file, err)),
+            );
+        }
+    }
 }
@@ -1,6 +1,12 @@
+pub mod conductor;
 pub mod dielectric;
-mod interactions;
+pub mod dispersion;
+pub mod interactions;
 pub mod lambertian;
 pub mod material;
 pub mod metal;
+mod microfacet;
+pub mod normal_mapped;
+pub mod principled;
+pub mod rough_dielectric;
 pub mod scatter_record;
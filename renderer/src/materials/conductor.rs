@@ -0,0 +1,129 @@
+use crate::{
+    math::{onb::Onb, random::rand_f32, vec3::Vec3},
+    shapes::hit_record::HitRecord,
+    util::{color::Color, ray::Ray},
+};
+
+use super::{
+    interactions::fresnel_conductor,
+    material::Material,
+    microfacet::{reflect_local, Ggx},
+    scatter_record::ScatterRecord,
+};
+
+/**
+ * Rough metal using GGX microfacet distribution and complex index of refraction eta + ik
+ */
+#[derive(Clone)]
+pub struct Conductor {
+    pub eta: Color,
+    pub k: Color,
+    pub roughness: f32,
+}
+
+impl Conductor {
+    pub fn gold(roughness: f32) -> Self {
+        Self {
+            eta: Color::new([0.143, 0.374, 1.442]),
+            k: Color::new([3.983, 2.385, 1.603]),
+            roughness,
+        }
+    }
+
+    pub fn copper(roughness: f32) -> Self {
+        Self {
+            eta: Color::new([0.200, 0.924, 1.102]),
+            k: Color::new([3.912, 2.452, 2.142]),
+            roughness,
+        }
+    }
+
+    pub fn aluminium(roughness: f32) -> Self {
+        Self {
+            eta: Color::new([1.657, 0.880, 0.521]),
+            k: Color::new([9.224, 6.270, 4.837]),
+            roughness,
+        }
+    }
+
+    fn distribution(&self) -> Ggx {
+        Ggx::isotropic(self.roughness)
+    }
+}
+
+impl Material for Conductor {
+    fn scatter(&self, ray_in: &Ray, hit_rec: &HitRecord) -> Option<ScatterRecord> {
+        let onb = Onb::from_w(&hit_rec.normal);
+        let wo = onb.to_local(&-ray_in.unit_direction());
+        if wo.z() <= 0.0 {
+            return None;
+        }
+
+        let ggx = self.distribution();
+        if ggx.is_smooth() {
+            let wi = Vec3::new([-wo.x(), -wo.y(), wo.z()]);
+            return Some(ScatterRecord {
+                scattered_ray: Ray::new(hit_rec.point, onb.to_world(&wi)),
+                attenuation: fresnel_conductor(wo.z(), &self.eta, &self.k),
+                pdf: None,
+            });
+        }
+
+        let h = ggx.sample_visible_normal(&wo, rand_f32(), rand_f32());
+        let wi = reflect_local(&wo, &h);
+        if wi.z() <= 0.0 {
+            return None;
+        }
+
+        // f * cos / pdf simplifies to F * G2 / G1
+        let fresnel = fresnel_conductor(wo.dot(&h), &self.eta, &self.k);
+        let attenuation = fresnel * (ggx.g2(&wo, &wi) / ggx.g1(&wo));
+        let pdf = ggx.visible_d(&wo, &h) / (4.0 * wo.dot(&h));
+
+        Some(ScatterRecord {
+            scattered_ray: Ray::new(hit_rec.point, onb.to_world(&wi)),
+            attenuation,
+            pdf: Some(pdf),
+        })
+    }
+
+    fn eval(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: &Vec3) -> Color {
+        let ggx = self.distribution();
+        if ggx.is_smooth() {
+            return Color::default();
+        }
+
+        let onb = Onb::from_w(&hit_rec.normal);
+        let wo = onb.to_local(&-ray_in.unit_direction());
+        let wi = onb.to_local(&direction.unit_vector());
+        if wo.z() <= 0.0 || wi.z() <= 0.0 {
+            return Color::default();
+        }
+
+        let h = (wo + wi).unit_vector();
+        let fresnel = fresnel_conductor(wo.dot(&h), &self.eta, &self.k);
+
+        fresnel * (ggx.d(&h) * ggx.g2(&wo, &wi) / (4.0 * wo.z()))
+    }
+
+    fn pdf(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: &Vec3) -> f32 {
+        let ggx = self.distribution();
+        if ggx.is_smooth() {
+            return 0.0;
+        }
+
+        let onb = Onb::from_w(&hit_rec.normal);
+        let wo = onb.to_local(&-ray_in.unit_direction());
+        let wi = onb.to_local(&direction.unit_vector());
+        if wo.z() <= 0.0 || wi.z() <= 0.0 {
+            return 0.0;
+        }
+
+        let h = (wo + wi).unit_vector();
+        ggx.visible_d(&wo, &h) / (4.0 * wo.dot(&h))
+    }
+
+    fn albedo(&self, _: &HitRecord) -> Color {
+        fresnel_conductor(1.0, &self.eta, &self.k)
+    }
+}
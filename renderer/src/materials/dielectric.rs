@@ -5,6 +5,7 @@ use crate::{
 };
 
 use super::{
+    dispersion::Dispersion,
     interactions::{reflect, refract},
     material::Material,
     scatter_record::ScatterRecord,
@@ -13,15 +14,27 @@ use super::{
 #[derive(Clone)]
 pub struct Dielectric {
     pub refraction_index: f32,
+    /// Replaces refraction_index for rays carrying a wavelength (spectral mode)
+    pub dispersion: Option<Dispersion>,
+}
+
+impl Dielectric {
+    fn refraction_index(&self, ray_in: &Ray) -> f32 {
+        match (&self.dispersion, ray_in.wavelength()) {
+            (Some(dispersion), Some(wavelength)) => dispersion.refraction_index(wavelength),
+            _ => self.refraction_index,
+        }
+    }
 }
 
 impl Material for Dielectric {
     fn scatter(&self, ray_in: &Ray, hit_rec: &HitRecord) -> Option<ScatterRecord> {
         let attenuation = Color::new([1.0, 1.0, 1.0]);
+        let refraction_index = self.refraction_index(ray_in);
         let refraction_ratio = if hit_rec.front_face {
-            1.0 / self.refraction_index
+            1.0 / refraction_index
         } else {
-            self.refraction_index
+            refraction_index
         };
 
         let unit_direction = Vec3::unit_vector(&ray_in.direction());
@@ -30,17 +43,23 @@ impl Material for Dielectric {
 
         let can_refract = refraction_ratio * sin_theta <= 1.0;
 
+        // Total internal reflection, otherwise reflect with probability of the Fresnel reflectance
         let direction = if !can_refract || reflectance(cos_theta, refraction_ratio) > rand_f32() {
-            refract(unit_direction, &hit_rec.normal, refraction_ratio)
-        } else {
             reflect(unit_direction, &hit_rec.normal)
+        } else {
+            refract(unit_direction, &hit_rec.normal, refraction_ratio)
         };
 
         Some(ScatterRecord {
             scattered_ray: Ray::new(hit_rec.point, direction),
             attenuation,
+            pdf: None,
         })
     }
+
+    fn is_wavelength_dependent(&self) -> bool {
+        self.dispersion.is_some()
+    }
 }
 
 /**
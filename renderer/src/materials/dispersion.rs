@@ -0,0 +1,46 @@
+/**
+ * Wavelength dependent index of refraction. Wavelengths are given in nanometers.
+ */
+#[derive(Clone)]
+pub enum Dispersion {
+    /// n = a + b / λ², b in µm²
+    Cauchy { a: f32, b: f32 },
+    /// n² = 1 + Σ bᵢλ² / (λ² - cᵢ), c in µm²
+    Sellmeier { b: [f32; 3], c: [f32; 3] },
+}
+
+impl Dispersion {
+    /**
+     * Schott N-BK7 crown glass
+     */
+    pub fn bk7() -> Self {
+        Dispersion::Sellmeier {
+            b: [1.039_612, 0.231_792_34, 1.010_469_5],
+            c: [0.006_000_699, 0.020_017_914, 103.560_65],
+        }
+    }
+
+    /**
+     * Dense flint glass with strong dispersion
+     */
+    pub fn flint() -> Self {
+        Dispersion::Cauchy {
+            a: 1.67,
+            b: 0.00743,
+        }
+    }
+
+    pub fn refraction_index(&self, wavelength: f32) -> f32 {
+        let micrometers_sq = (wavelength / 1000.0).powi(2);
+
+        match self {
+            Dispersion::Cauchy { a, b } => a + b / micrometers_sq,
+            Dispersion::Sellmeier { b, c } => {
+                let sum: f32 = (0..3)
+                    .map(|i| b[i] * micrometers_sq / (micrometers_sq - c[i]))
+                    .sum();
+                (1.0 + sum).sqrt()
+            }
+        }
+    }
+}
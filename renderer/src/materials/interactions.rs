@@ -1,4 +1,4 @@
-use crate::math::vec3::Vec3;
+use crate::{math::vec3::Vec3, util::color::Color};
 
 pub fn refract(unit_vec: Vec3, normal: &Vec3, refraction_ratio: f32) -> Vec3 {
     let cos_theta = f32::min((-unit_vec).dot(normal), 1.0);
@@ -9,6 +9,62 @@ pub fn refract(unit_vec: Vec3, normal: &Vec3, refraction_ratio: f32) -> Vec3 {
     ray_out_perpendicular + ray_out_parallel
 }
 
+/**
+ * Mirrors vec about the plane with given unit normal
+ */
 pub fn reflect(vec: Vec3, normal: &Vec3) -> Vec3 {
-    return (vec - 2.0) * vec.dot(normal) * normal;
+    vec - normal * (2.0 * vec.dot(normal))
+}
+
+/**
+ * Unpolarized Fresnel reflectance of a dielectric interface.
+ * eta is the ratio of transmitted to incident index.
+ */
+pub fn fresnel_dielectric(cos_i: f32, eta: f32) -> f32 {
+    let cos_i = cos_i.clamp(-1.0, 1.0);
+    let (cos_i, eta) = if cos_i < 0.0 {
+        (-cos_i, 1.0 / eta)
+    } else {
+        (cos_i, eta)
+    };
+
+    let sin2_t = (1.0 - cos_i * cos_i) / (eta * eta);
+    if sin2_t >= 1.0 {
+        return 1.0;
+    }
+    let cos_t = (1.0 - sin2_t).sqrt();
+
+    let r_parallel = (eta * cos_i - cos_t) / (eta * cos_i + cos_t);
+    let r_perpendicular = (cos_i - eta * cos_t) / (cos_i + eta * cos_t);
+    (r_parallel * r_parallel + r_perpendicular * r_perpendicular) / 2.0
+}
+
+/**
+ * Fresnel reflectance of a conductor with complex index of refraction eta + ik, per color channel
+ */
+pub fn fresnel_conductor(cos_i: f32, eta: &Color, k: &Color) -> Color {
+    Color::new([
+        fresnel_complex(cos_i, eta.x(), k.x()),
+        fresnel_complex(cos_i, eta.y(), k.y()),
+        fresnel_complex(cos_i, eta.z(), k.z()),
+    ])
+}
+
+fn fresnel_complex(cos_i: f32, eta: f32, k: f32) -> f32 {
+    let cos2 = cos_i.clamp(0.0, 1.0).powi(2);
+    let sin2 = 1.0 - cos2;
+
+    let t0 = eta * eta - k * k - sin2;
+    let a2_plus_b2 = (t0 * t0 + 4.0 * eta * eta * k * k).sqrt();
+    let a = ((a2_plus_b2 + t0) / 2.0).max(0.0).sqrt();
+
+    let t1 = a2_plus_b2 + cos2;
+    let t2 = 2.0 * a * cos2.sqrt();
+    let r_perpendicular = (t1 - t2) / (t1 + t2);
+
+    let t3 = cos2 * a2_plus_b2 + sin2 * sin2;
+    let t4 = t2 * sin2;
+    let r_parallel = r_perpendicular * (t3 - t4) / (t3 + t4);
+
+    (r_parallel + r_perpendicular) / 2.0
 }
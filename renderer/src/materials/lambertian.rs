@@ -1,3 +1,5 @@
+use std::f32::consts::PI;
+
 use crate::math::vec3::Vec3;
 use crate::shapes::hit_record;
 use crate::util::color::Color;
@@ -20,10 +22,30 @@ impl Material for Lambertian {
 
         let scattered_ray = Ray::new(hit_rec.point, scatter_direction);
         let attenuation = self.albedo;
+        let pdf = self.pdf(&scattered_ray, hit_rec, &scatter_direction);
 
         Some(ScatterRecord {
             scattered_ray,
             attenuation,
+            pdf: Some(pdf),
         })
     }
+
+    fn eval(&self, _: &Ray, hit_rec: &hit_record::HitRecord, direction: &Vec3) -> Color {
+        let cos = direction.unit_vector().dot(&hit_rec.normal);
+        if cos <= 0.0 {
+            return Color::default();
+        }
+
+        self.albedo * (cos / PI)
+    }
+
+    fn pdf(&self, _: &Ray, hit_rec: &hit_record::HitRecord, direction: &Vec3) -> f32 {
+        // Sampling normal + unit vector gives cosine weighted directions
+        direction.unit_vector().dot(&hit_rec.normal).max(0.0) / PI
+    }
+
+    fn albedo(&self, _: &hit_record::HitRecord) -> Color {
+        self.albedo
+    }
 }
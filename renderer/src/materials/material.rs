@@ -1,26 +1,133 @@
-use crate::{shapes::hit_record::HitRecord, util::ray::Ray};
+use crate::{
+    math::vec3::Vec3,
+    shapes::hit_record::HitRecord,
+    util::{color::Color, ray::Ray},
+};
 
 use super::{
-    dielectric::Dielectric, lambertian::Lambertian, metal::Metal, scatter_record::ScatterRecord,
+    conductor::Conductor, dielectric::Dielectric, lambertian::Lambertian, metal::Metal,
+    normal_mapped::NormalMapped, principled::Principled, rough_dielectric::RoughDielectric,
+    scatter_record::ScatterRecord,
 };
 
 #[derive(Clone)]
 pub enum MaterialType {
+    Conductor(Conductor),
     Dielectric(Dielectric),
     Lambertian(Lambertian),
     Metal(Metal),
+    NormalMapped(Box<NormalMapped>),
+    Principled(Box<Principled>),
+    RoughDielectric(RoughDielectric),
+}
+
+impl MaterialType {
+    /**
+     * Stable number for each kind of material, used for material ID render pass
+     */
+    pub fn id(&self) -> usize {
+        match self {
+            MaterialType::Conductor(_) => 0,
+            MaterialType::Dielectric(_) => 1,
+            MaterialType::Lambertian(_) => 2,
+            MaterialType::Metal(_) => 3,
+            MaterialType::NormalMapped(mapped) => mapped.material.id(),
+            MaterialType::Principled(_) => 4,
+            MaterialType::RoughDielectric(_) => 5,
+        }
+    }
 }
 
 impl Material for MaterialType {
     fn scatter(&self, ray_in: &Ray, hit_rec: &HitRecord) -> Option<ScatterRecord> {
         match self {
+            MaterialType::Conductor(conductor) => conductor.scatter(ray_in, hit_rec),
             MaterialType::Dielectric(dielectric) => dielectric.scatter(ray_in, hit_rec),
             MaterialType::Metal(metal) => metal.scatter(ray_in, hit_rec),
             MaterialType::Lambertian(lambertian) => lambertian.scatter(ray_in, hit_rec),
+            MaterialType::NormalMapped(mapped) => mapped.scatter(ray_in, hit_rec),
+            MaterialType::Principled(principled) => principled.scatter(ray_in, hit_rec),
+            MaterialType::RoughDielectric(dielectric) => dielectric.scatter(ray_in, hit_rec),
+        }
+    }
+
+    fn eval(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: &Vec3) -> Color {
+        match self {
+            MaterialType::Conductor(conductor) => conductor.eval(ray_in, hit_rec, direction),
+            MaterialType::Dielectric(dielectric) => dielectric.eval(ray_in, hit_rec, direction),
+            MaterialType::Metal(metal) => metal.eval(ray_in, hit_rec, direction),
+            MaterialType::Lambertian(lambertian) => lambertian.eval(ray_in, hit_rec, direction),
+            MaterialType::NormalMapped(mapped) => mapped.eval(ray_in, hit_rec, direction),
+            MaterialType::Principled(principled) => principled.eval(ray_in, hit_rec, direction),
+            MaterialType::RoughDielectric(dielectric) => {
+                dielectric.eval(ray_in, hit_rec, direction)
+            }
+        }
+    }
+
+    fn pdf(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: &Vec3) -> f32 {
+        match self {
+            MaterialType::Conductor(conductor) => conductor.pdf(ray_in, hit_rec, direction),
+            MaterialType::Dielectric(dielectric) => dielectric.pdf(ray_in, hit_rec, direction),
+            MaterialType::Metal(metal) => metal.pdf(ray_in, hit_rec, direction),
+            MaterialType::Lambertian(lambertian) => lambertian.pdf(ray_in, hit_rec, direction),
+            MaterialType::NormalMapped(mapped) => mapped.pdf(ray_in, hit_rec, direction),
+            MaterialType::Principled(principled) => principled.pdf(ray_in, hit_rec, direction),
+            MaterialType::RoughDielectric(dielectric) => dielectric.pdf(ray_in, hit_rec, direction),
+        }
+    }
+
+    fn albedo(&self, hit_rec: &HitRecord) -> Color {
+        match self {
+            MaterialType::Conductor(conductor) => conductor.albedo(hit_rec),
+            MaterialType::Dielectric(dielectric) => dielectric.albedo(hit_rec),
+            MaterialType::Metal(metal) => metal.albedo(hit_rec),
+            MaterialType::Lambertian(lambertian) => lambertian.albedo(hit_rec),
+            MaterialType::NormalMapped(mapped) => mapped.albedo(hit_rec),
+            MaterialType::Principled(principled) => principled.albedo(hit_rec),
+            MaterialType::RoughDielectric(dielectric) => dielectric.albedo(hit_rec),
+        }
+    }
+
+    fn is_wavelength_dependent(&self) -> bool {
+        match self {
+            MaterialType::Dielectric(dielectric) => dielectric.is_wavelength_dependent(),
+            MaterialType::NormalMapped(mapped) => mapped.material.is_wavelength_dependent(),
+            _ => false,
         }
     }
 }
 
 pub trait Material {
     fn scatter(&self, ray_in: &Ray, hit_rec: &HitRecord) -> Option<ScatterRecord>;
+
+    /**
+     * BSDF multiplied by cosine of the scattered direction. Zero for directions the material
+     * can only reach through a specular (delta) lobe.
+     */
+    fn eval(&self, _ray_in: &Ray, _hit_rec: &HitRecord, _direction: &Vec3) -> Color {
+        Color::default()
+    }
+
+    /**
+     * Solid angle density with which scatter samples given direction. Used for MIS weights.
+     */
+    fn pdf(&self, _ray_in: &Ray, _hit_rec: &HitRecord, _direction: &Vec3) -> f32 {
+        0.0
+    }
+
+    /**
+     * Surface color at hit point without lighting, used for albedo render pass
+     */
+    fn albedo(&self, _hit_rec: &HitRecord) -> Color {
+        Color::new([1.0, 1.0, 1.0])
+    }
+
+    /**
+     * Whether scattering depends on the ray wavelength. Spectral paths drop their secondary
+     * wavelengths after such an interaction.
+     */
+    fn is_wavelength_dependent(&self) -> bool {
+        false
+    }
 }
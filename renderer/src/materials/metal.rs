@@ -29,6 +29,11 @@ impl Material for Metal {
         Some(ScatterRecord {
             scattered_ray,
             attenuation,
+            pdf: None,
         })
     }
+
+    fn albedo(&self, _: &HitRecord) -> Color {
+        self.albedo
+    }
 }
@@ -0,0 +1,154 @@
+use std::f32::consts::PI;
+
+use crate::math::vec3::Vec3;
+
+/**
+ * Below this alpha the surface is treated as perfectly smooth (delta lobe)
+ */
+const SMOOTH_ALPHA: f32 = 1e-3;
+
+/**
+ * GGX / Trowbridge-Reitz microfacet distribution.
+ * All directions are in the local shading frame where z is the macro surface normal.
+ */
+#[derive(Clone, Copy)]
+pub struct Ggx {
+    pub alpha_x: f32,
+    pub alpha_y: f32,
+}
+
+impl Ggx {
+    pub fn isotropic(roughness: f32) -> Self {
+        Self::anisotropic(roughness, 0.0)
+    }
+
+    /**
+     * Perceptual roughness is squared into alpha. Anisotropy in range [0, 1] stretches highlight along tangent.
+     */
+    pub fn anisotropic(roughness: f32, anisotropy: f32) -> Self {
+        let alpha = roughness.clamp(0.0, 1.0).powi(2);
+        let aspect = (1.0 - 0.9 * anisotropy.clamp(0.0, 1.0)).sqrt();
+
+        Self {
+            alpha_x: alpha / aspect,
+            alpha_y: alpha * aspect,
+        }
+    }
+
+    pub fn is_smooth(&self) -> bool {
+        self.alpha_x.max(self.alpha_y) < SMOOTH_ALPHA
+    }
+
+    /**
+     * Normal distribution function D(h)
+     */
+    pub fn d(&self, h: &Vec3) -> f32 {
+        if h.z() <= 0.0 {
+            return 0.0;
+        }
+
+        let x = h.x() / self.alpha_x;
+        let y = h.y() / self.alpha_y;
+        let denom = x * x + y * y + h.z() * h.z();
+
+        1.0 / (PI * self.alpha_x * self.alpha_y * denom * denom)
+    }
+
+    /**
+     * Smith auxiliary function Λ(w)
+     */
+    pub fn lambda(&self, w: &Vec3) -> f32 {
+        let cos2 = w.z() * w.z();
+        if cos2 == 0.0 {
+            return f32::INFINITY;
+        }
+
+        let x = self.alpha_x * w.x();
+        let y = self.alpha_y * w.y();
+        let tan2_alpha2 = (x * x + y * y) / cos2;
+
+        ((1.0 + tan2_alpha2).sqrt() - 1.0) / 2.0
+    }
+
+    /**
+     * Smith masking function G1(w)
+     */
+    pub fn g1(&self, w: &Vec3) -> f32 {
+        1.0 / (1.0 + self.lambda(w))
+    }
+
+    /**
+     * Height-correlated masking-shadowing function G2(wo, wi)
+     */
+    pub fn g2(&self, wo: &Vec3, wi: &Vec3) -> f32 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    /**
+     * Distribution of normals visible from wo, D_wo(h)
+     */
+    pub fn visible_d(&self, wo: &Vec3, h: &Vec3) -> f32 {
+        let cos_o = wo.z().abs();
+        if cos_o == 0.0 {
+            return 0.0;
+        }
+
+        self.g1(wo) * wo.dot(h).max(0.0) * self.d(h) / cos_o
+    }
+
+    /**
+     * Samples a visible microfacet normal (Heitz 2018). wo must be in the upper hemisphere.
+     */
+    pub fn sample_visible_normal(&self, wo: &Vec3, u1: f32, u2: f32) -> Vec3 {
+        // Stretch view vector so the distribution becomes a hemisphere
+        let vh = Vec3::new([self.alpha_x * wo.x(), self.alpha_y * wo.y(), wo.z()]).unit_vector();
+
+        let len_sq = vh.x() * vh.x() + vh.y() * vh.y();
+        let t1 = if len_sq > 0.0 {
+            Vec3::new([-vh.y(), vh.x(), 0.0]) / len_sq.sqrt()
+        } else {
+            Vec3::new([1.0, 0.0, 0.0])
+        };
+        let t2 = vh.cross(&t1);
+
+        // Uniform point on disk, warped towards projected hemisphere
+        let r = u1.sqrt();
+        let phi = 2.0 * PI * u2;
+        let p1 = r * phi.cos();
+        let s = 0.5 * (1.0 + vh.z());
+        let p2 = (1.0 - s) * (1.0 - p1 * p1).sqrt() + s * r * phi.sin();
+        let p3 = (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt();
+
+        let nh = t1 * p1 + t2 * p2 + vh * p3;
+
+        // Unstretch
+        Vec3::new([
+            self.alpha_x * nh.x(),
+            self.alpha_y * nh.y(),
+            nh.z().max(1e-6),
+        ])
+        .unit_vector()
+    }
+}
+
+/**
+ * Mirrors wo about the microfacet normal h
+ */
+pub fn reflect_local(wo: &Vec3, h: &Vec3) -> Vec3 {
+    h * (2.0 * wo.dot(h)) - wo
+}
+
+/**
+ * Refracts wo through microfacet h. eta is the ratio of transmitted to incident index.
+ * Returns None on total internal reflection.
+ */
+pub fn refract_local(wo: &Vec3, h: &Vec3, eta: f32) -> Option<Vec3> {
+    let cos_i = wo.dot(h);
+    let sin2_t = (1.0 - cos_i * cos_i).max(0.0) / (eta * eta);
+    if sin2_t >= 1.0 {
+        return None;
+    }
+
+    let cos_t = (1.0 - sin2_t).sqrt();
+    Some(-*wo / eta + h * (cos_i / eta - cos_t))
+}
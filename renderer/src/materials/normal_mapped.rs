@@ -0,0 +1,95 @@
+use crate::{
+    math::vec3::Vec3,
+    shapes::hit_record::{orthogonal_tangent, HitRecord},
+    textures::texture::{Texture, TextureType},
+    util::{color::Color, ray::Ray},
+};
+
+use super::{
+    material::{Material, MaterialType},
+    scatter_record::ScatterRecord,
+};
+
+/**
+ * UV step used for height map finite differences
+ */
+const BUMP_DELTA: f32 = 1.0 / 1024.0;
+
+#[derive(Clone)]
+pub enum SurfaceMap {
+    /// Tangent space normal map, RGB in range [0, 1] maps to XYZ in range [-1, 1]
+    Normal { texture: TextureType, strength: f32 },
+    /// Height map, first channel is the height. Scale is height per UV unit.
+    Bump { height: TextureType, scale: f32 },
+}
+
+/**
+ * Perturbs shading normal with a normal or bump map before passing the hit to the inner material
+ */
+#[derive(Clone)]
+pub struct NormalMapped {
+    pub material: MaterialType,
+    pub map: SurfaceMap,
+}
+
+impl NormalMapped {
+    fn perturb(&self, ray_in: &Ray, hit_rec: &HitRecord) -> HitRecord {
+        let tangent = hit_rec.tangent;
+        // Back faces see the map mirrored. Normal and tangent are already flipped there, the
+        // bitangent has to follow so the perturbation is the front one turned around.
+        let bitangent = match hit_rec.front_face {
+            true => hit_rec.normal.cross(&tangent),
+            false => -hit_rec.normal.cross(&tangent),
+        };
+        let (u, v, point) = (hit_rec.u, hit_rec.v, &hit_rec.point);
+
+        let normal = match &self.map {
+            SurfaceMap::Normal { texture, strength } => {
+                let value = texture.value(u, v, point) * 2.0 - 1.0;
+                tangent * (value.x() * strength)
+                    + bitangent * (value.y() * strength)
+                    + hit_rec.normal * value.z()
+            }
+            SurfaceMap::Bump { height, scale } => {
+                let center = height.scalar(u, v, point);
+                let du = (height.scalar(u + BUMP_DELTA, v, point) - center) / BUMP_DELTA;
+                let dv = (height.scalar(u, v + BUMP_DELTA, point) - center) / BUMP_DELTA;
+
+                hit_rec.normal - (tangent * du + bitangent * dv) * *scale
+            }
+        };
+
+        // Shading normal turned away from the viewer would leak light through the surface
+        if normal.near_zero() || normal.dot(&ray_in.direction()) >= 0.0 {
+            return hit_rec.clone();
+        }
+
+        let normal = normal.unit_vector();
+        HitRecord {
+            normal,
+            tangent: orthogonal_tangent(&tangent, &normal),
+            ..hit_rec.clone()
+        }
+    }
+}
+
+impl Material for NormalMapped {
+    fn scatter(&self, ray_in: &Ray, hit_rec: &HitRecord) -> Option<ScatterRecord> {
+        self.material
+            .scatter(ray_in, &self.perturb(ray_in, hit_rec))
+    }
+
+    fn eval(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: &Vec3) -> Color {
+        self.material
+            .eval(ray_in, &self.perturb(ray_in, hit_rec), direction)
+    }
+
+    fn pdf(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: &Vec3) -> f32 {
+        self.material
+            .pdf(ray_in, &self.perturb(ray_in, hit_rec), direction)
+    }
+
+    fn albedo(&self, hit_rec: &HitRecord) -> Color {
+        self.material.albedo(hit_rec)
+    }
+}
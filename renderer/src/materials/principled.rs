@@ -0,0 +1,306 @@
+use std::f32::consts::PI;
+
+use crate::{
+    math::{onb::Onb, random::rand_f32, vec3::Vec3},
+    shapes::hit_record::HitRecord,
+    textures::texture::{Texture, TextureType},
+    util::{color::Color, ray::Ray},
+};
+
+use super::{
+    material::Material,
+    microfacet::{reflect_local, Ggx},
+    rough_dielectric::RoughDielectric,
+    scatter_record::ScatterRecord,
+};
+
+/**
+ * Clearcoat is a fixed glossy layer with index of refraction 1.5
+ */
+const CLEARCOAT_ROUGHNESS: f32 = 0.1;
+const CLEARCOAT_F0: f32 = 0.04;
+
+/**
+ * Roughness is kept above this so every lobe stays sampleable with a finite pdf
+ */
+const MIN_ROUGHNESS: f32 = 0.045;
+
+/**
+ * Disney style principled BSDF. Scalar parameters are in range [0, 1] and read the first texture channel.
+ * Lobes: diffuse, sheen, specular (dielectric or metallic), clearcoat and rough transmission.
+ */
+#[derive(Clone)]
+pub struct Principled {
+    pub base_color: TextureType,
+    pub metallic: TextureType,
+    pub roughness: TextureType,
+    /// Dielectric reflectance, 0.5 equals F0 of 4%
+    pub specular: TextureType,
+    pub sheen: TextureType,
+    pub clearcoat: TextureType,
+    pub transmission: TextureType,
+    pub anisotropy: TextureType,
+    pub refraction_index: f32,
+}
+
+impl Default for Principled {
+    fn default() -> Self {
+        Self {
+            base_color: TextureType::constant(0.8),
+            metallic: TextureType::constant(0.0),
+            roughness: TextureType::constant(0.5),
+            specular: TextureType::constant(0.5),
+            sheen: TextureType::constant(0.0),
+            clearcoat: TextureType::constant(0.0),
+            transmission: TextureType::constant(0.0),
+            anisotropy: TextureType::constant(0.0),
+            refraction_index: 1.5,
+        }
+    }
+}
+
+/**
+ * Principled parameters evaluated at a single surface point
+ */
+struct Lobes {
+    base_color: Color,
+    metallic: f32,
+    specular: f32,
+    sheen: f32,
+    clearcoat: f32,
+    transmission: f32,
+    front_face: bool,
+    ggx: Ggx,
+    clearcoat_ggx: Ggx,
+    dielectric: RoughDielectric,
+}
+
+struct LobeProbabilities {
+    diffuse: f32,
+    specular: f32,
+    clearcoat: f32,
+}
+
+impl Principled {
+    fn lobes(&self, hit_rec: &HitRecord) -> Lobes {
+        let (u, v, p) = (hit_rec.u, hit_rec.v, &hit_rec.point);
+        let roughness = self.roughness.scalar(u, v, p).clamp(MIN_ROUGHNESS, 1.0);
+
+        Lobes {
+            base_color: self.base_color.value(u, v, p),
+            metallic: self.metallic.scalar(u, v, p).clamp(0.0, 1.0),
+            specular: self.specular.scalar(u, v, p).clamp(0.0, 1.0),
+            sheen: self.sheen.scalar(u, v, p).clamp(0.0, 1.0),
+            clearcoat: self.clearcoat.scalar(u, v, p).clamp(0.0, 1.0),
+            transmission: self.transmission.scalar(u, v, p).clamp(0.0, 1.0),
+            front_face: hit_rec.front_face,
+            ggx: Ggx::anisotropic(roughness, self.anisotropy.scalar(u, v, p)),
+            clearcoat_ggx: Ggx::isotropic(CLEARCOAT_ROUGHNESS),
+            dielectric: RoughDielectric {
+                refraction_index: self.refraction_index,
+                roughness,
+            },
+        }
+    }
+
+    fn local_directions(ray_in: &Ray, hit_rec: &HitRecord, direction: &Vec3) -> (Vec3, Vec3) {
+        let onb = Onb::from_normal_tangent(&hit_rec.normal, &hit_rec.tangent);
+        let wo = onb.to_local(&-ray_in.unit_direction());
+        let wi = onb.to_local(&direction.unit_vector());
+        (wo, wi)
+    }
+}
+
+impl Lobes {
+    fn diffuse_weight(&self) -> f32 {
+        (1.0 - self.metallic) * (1.0 - self.transmission)
+    }
+
+    fn transmission_weight(&self) -> f32 {
+        (1.0 - self.metallic) * self.transmission
+    }
+
+    /**
+     * Clearcoat only sits on the outside of the surface
+     */
+    fn clearcoat_weight(&self) -> f32 {
+        match self.front_face {
+            true => 0.25 * self.clearcoat,
+            false => 0.0,
+        }
+    }
+
+    /**
+     * Probability of picking each lobe, transmission gets the remainder
+     */
+    fn probabilities(&self) -> LobeProbabilities {
+        let diffuse = self.diffuse_weight();
+        let specular = self.metallic + self.diffuse_weight();
+        let clearcoat = self.clearcoat_weight();
+        let total = diffuse + specular + clearcoat + self.transmission_weight();
+
+        LobeProbabilities {
+            diffuse: diffuse / total,
+            specular: specular / total,
+            clearcoat: clearcoat / total,
+        }
+    }
+
+    fn specular_fresnel(&self, cos: f32) -> Color {
+        let dielectric_f0 = 0.08 * self.specular;
+        let white = Color::new([1.0, 1.0, 1.0]);
+
+        schlick(&self.base_color, cos) * self.metallic
+            + schlick(&(white * dielectric_f0), cos) * self.diffuse_weight()
+    }
+
+    fn eval(&self, wo: &Vec3, wi: &Vec3) -> Color {
+        if wo.z() <= 0.0 {
+            return Color::default();
+        }
+
+        // Transmission is the only lobe reaching the other side
+        if wi.z() <= 0.0 {
+            let eta = self.dielectric.eta(self.front_face);
+            return self.base_color
+                * (self.transmission_weight() * self.dielectric.eval_local(wo, wi, eta));
+        }
+
+        let h = (wo + wi).unit_vector();
+        let cos_d = wi.dot(&h);
+
+        // Light reflected by the dielectric specular layer never reaches the diffuse base, on
+        // the way in or out, which keeps the BSDF reciprocal
+        let dielectric_f0 = 0.08 * self.specular;
+        let base_weight = self.diffuse_weight()
+            * (1.0 - schlick_scalar(dielectric_f0, wo.z()))
+            * (1.0 - schlick_scalar(dielectric_f0, wi.z()));
+        let diffuse = self.base_color * (base_weight * wi.z() / PI);
+        let sheen = self.sheen * base_weight * schlick_weight(cos_d) * wi.z();
+
+        let specular = self.specular_fresnel(wo.dot(&h))
+            * (self.ggx.d(&h) * self.ggx.g2(wo, wi) / (4.0 * wo.z()));
+
+        let clearcoat = self.clearcoat_weight()
+            * schlick_scalar(CLEARCOAT_F0, wo.dot(&h))
+            * self.clearcoat_ggx.d(&h)
+            * self.clearcoat_ggx.g2(wo, wi)
+            / (4.0 * wo.z());
+
+        let eta = self.dielectric.eta(self.front_face);
+        let transmission_reflection =
+            self.transmission_weight() * self.dielectric.eval_local(wo, wi, eta);
+
+        diffuse + specular + (sheen + clearcoat + transmission_reflection)
+    }
+
+    fn pdf(&self, wo: &Vec3, wi: &Vec3) -> f32 {
+        if wo.z() <= 0.0 {
+            return 0.0;
+        }
+
+        let probabilities = self.probabilities();
+        let eta = self.dielectric.eta(self.front_face);
+        let transmission =
+            (1.0 - probabilities.diffuse - probabilities.specular - probabilities.clearcoat)
+                .max(0.0)
+                * self.dielectric.pdf_local(wo, wi, eta);
+
+        if wi.z() <= 0.0 {
+            return transmission;
+        }
+
+        let h = (wo + wi).unit_vector();
+        let diffuse = wi.z() / PI;
+        let specular = self.ggx.visible_d(wo, &h) / (4.0 * wo.dot(&h));
+        let clearcoat = self.clearcoat_ggx.visible_d(wo, &h) / (4.0 * wo.dot(&h));
+
+        probabilities.diffuse * diffuse
+            + probabilities.specular * specular
+            + probabilities.clearcoat * clearcoat
+            + transmission
+    }
+
+    fn sample(&self, wo: &Vec3) -> Option<Vec3> {
+        let probabilities = self.probabilities();
+        let mut pick = rand_f32();
+
+        if pick < probabilities.diffuse {
+            return Some(Vec3::random_cosine_direction());
+        }
+        pick -= probabilities.diffuse;
+
+        if pick < probabilities.specular {
+            let h = self.ggx.sample_visible_normal(wo, rand_f32(), rand_f32());
+            return upper_hemisphere(reflect_local(wo, &h));
+        }
+        pick -= probabilities.specular;
+
+        if pick < probabilities.clearcoat {
+            let h = self
+                .clearcoat_ggx
+                .sample_visible_normal(wo, rand_f32(), rand_f32());
+            return upper_hemisphere(reflect_local(wo, &h));
+        }
+
+        let eta = self.dielectric.eta(self.front_face);
+        self.dielectric.sample_local(wo, eta)
+    }
+}
+
+impl Material for Principled {
+    fn scatter(&self, ray_in: &Ray, hit_rec: &HitRecord) -> Option<ScatterRecord> {
+        let lobes = self.lobes(hit_rec);
+        let onb = Onb::from_normal_tangent(&hit_rec.normal, &hit_rec.tangent);
+        let wo = onb.to_local(&-ray_in.unit_direction());
+        if wo.z() <= 0.0 {
+            return None;
+        }
+
+        let wi = lobes.sample(&wo)?;
+        let pdf = lobes.pdf(&wo, &wi);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        Some(ScatterRecord {
+            scattered_ray: Ray::new(hit_rec.point, onb.to_world(&wi)),
+            attenuation: lobes.eval(&wo, &wi) / pdf,
+            pdf: Some(pdf),
+        })
+    }
+
+    fn eval(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: &Vec3) -> Color {
+        let (wo, wi) = Principled::local_directions(ray_in, hit_rec, direction);
+        self.lobes(hit_rec).eval(&wo, &wi)
+    }
+
+    fn pdf(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: &Vec3) -> f32 {
+        let (wo, wi) = Principled::local_directions(ray_in, hit_rec, direction);
+        self.lobes(hit_rec).pdf(&wo, &wi)
+    }
+
+    fn albedo(&self, hit_rec: &HitRecord) -> Color {
+        self.base_color.value(hit_rec.u, hit_rec.v, &hit_rec.point)
+    }
+}
+
+/**
+ * Reflections below the surface are shadowed, they must not pass as transmission samples
+ */
+fn upper_hemisphere(direction: Vec3) -> Option<Vec3> {
+    (direction.z() > 0.0).then_some(direction)
+}
+
+fn schlick_weight(cos: f32) -> f32 {
+    (1.0 - cos.clamp(0.0, 1.0)).powi(5)
+}
+
+fn schlick_scalar(f0: f32, cos: f32) -> f32 {
+    f0 + (1.0 - f0) * schlick_weight(cos)
+}
+
+fn schlick(f0: &Color, cos: f32) -> Color {
+    let white = Color::new([1.0, 1.0, 1.0]);
+    f0 + (white - f0) * schlick_weight(cos)
+}
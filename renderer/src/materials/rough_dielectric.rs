@@ -0,0 +1,182 @@
+use crate::{
+    math::{onb::Onb, random::rand_f32, vec3::Vec3},
+    shapes::hit_record::HitRecord,
+    util::{color::Color, ray::Ray},
+};
+
+use super::{
+    interactions::fresnel_dielectric,
+    material::Material,
+    microfacet::{reflect_local, refract_local, Ggx},
+    scatter_record::ScatterRecord,
+};
+
+/**
+ * Frosted glass using GGX microfacet distribution with visible normal sampling
+ */
+#[derive(Clone)]
+pub struct RoughDielectric {
+    pub refraction_index: f32,
+    pub roughness: f32,
+}
+
+impl RoughDielectric {
+    fn distribution(&self) -> Ggx {
+        Ggx::isotropic(self.roughness)
+    }
+
+    /**
+     * Ratio of transmitted to incident index, normal always points to incident side
+     */
+    pub(super) fn eta(&self, front_face: bool) -> f32 {
+        if front_face {
+            self.refraction_index
+        } else {
+            1.0 / self.refraction_index
+        }
+    }
+
+    /**
+     * Samples scattered direction in local shading frame. Only valid for rough surfaces.
+     */
+    pub(super) fn sample_local(&self, wo: &Vec3, eta: f32) -> Option<Vec3> {
+        let ggx = self.distribution();
+        let h = ggx.sample_visible_normal(wo, rand_f32(), rand_f32());
+        let fresnel = fresnel_dielectric(wo.dot(&h), eta);
+
+        // Lobe is picked with probability equal to its Fresnel term so F cancels out of the weight
+        match refract_local(wo, &h, eta) {
+            Some(refracted) if rand_f32() >= fresnel => (refracted.z() < 0.0).then_some(refracted),
+            _ => {
+                let reflected = reflect_local(wo, &h);
+                (reflected.z() > 0.0).then_some(reflected)
+            }
+        }
+    }
+
+    pub(super) fn eval_local(&self, wo: &Vec3, wi: &Vec3, eta: f32) -> f32 {
+        let ggx = self.distribution();
+
+        match half_vector(wo, wi, eta) {
+            Some(h) if wi.z() > 0.0 => {
+                let fresnel = fresnel_dielectric(wo.dot(&h), eta);
+                ggx.d(&h) * ggx.g2(wo, wi) * fresnel / (4.0 * wo.z())
+            }
+            Some(h) => {
+                let fresnel = fresnel_dielectric(wo.dot(&h), eta);
+                let denom = wi.dot(&h) + wo.dot(&h) / eta;
+                ggx.d(&h) * ggx.g2(wo, wi) * (1.0 - fresnel) * (wi.dot(&h) * wo.dot(&h)).abs()
+                    / (wo.z() * denom * denom)
+            }
+            None => 0.0,
+        }
+    }
+
+    pub(super) fn pdf_local(&self, wo: &Vec3, wi: &Vec3, eta: f32) -> f32 {
+        let ggx = self.distribution();
+
+        match half_vector(wo, wi, eta) {
+            Some(h) if wi.z() > 0.0 => {
+                let fresnel = fresnel_dielectric(wo.dot(&h), eta);
+                fresnel * ggx.visible_d(wo, &h) / (4.0 * wo.dot(&h))
+            }
+            Some(h) => {
+                let fresnel = fresnel_dielectric(wo.dot(&h), eta);
+                let denom = wi.dot(&h) + wo.dot(&h) / eta;
+                (1.0 - fresnel) * ggx.visible_d(wo, &h) * wi.dot(&h).abs() / (denom * denom)
+            }
+            None => 0.0,
+        }
+    }
+}
+
+impl Material for RoughDielectric {
+    fn scatter(&self, ray_in: &Ray, hit_rec: &HitRecord) -> Option<ScatterRecord> {
+        let onb = Onb::from_w(&hit_rec.normal);
+        let wo = onb.to_local(&-ray_in.unit_direction());
+        if wo.z() <= 0.0 {
+            return None;
+        }
+
+        let eta = self.eta(hit_rec.front_face);
+        let ggx = self.distribution();
+        let attenuation = Color::new([1.0, 1.0, 1.0]);
+
+        if ggx.is_smooth() {
+            let h = Vec3::new([0.0, 0.0, 1.0]);
+            let wi = match refract_local(&wo, &h, eta) {
+                Some(refracted) if rand_f32() >= fresnel_dielectric(wo.z(), eta) => refracted,
+                _ => reflect_local(&wo, &h),
+            };
+
+            return Some(ScatterRecord {
+                scattered_ray: Ray::new(hit_rec.point, onb.to_world(&wi)),
+                attenuation,
+                pdf: None,
+            });
+        }
+
+        let wi = self.sample_local(&wo, eta)?;
+
+        Some(ScatterRecord {
+            scattered_ray: Ray::new(hit_rec.point, onb.to_world(&wi)),
+            attenuation: attenuation * (ggx.g2(&wo, &wi) / ggx.g1(&wo)),
+            pdf: Some(self.pdf_local(&wo, &wi, eta)),
+        })
+    }
+
+    fn eval(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: &Vec3) -> Color {
+        if self.distribution().is_smooth() {
+            return Color::default();
+        }
+
+        let onb = Onb::from_w(&hit_rec.normal);
+        let wo = onb.to_local(&-ray_in.unit_direction());
+        let wi = onb.to_local(&direction.unit_vector());
+        let value = self.eval_local(&wo, &wi, self.eta(hit_rec.front_face));
+
+        Color::new([value, value, value])
+    }
+
+    fn pdf(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: &Vec3) -> f32 {
+        if self.distribution().is_smooth() {
+            return 0.0;
+        }
+
+        let onb = Onb::from_w(&hit_rec.normal);
+        let wo = onb.to_local(&-ray_in.unit_direction());
+        let wi = onb.to_local(&direction.unit_vector());
+
+        self.pdf_local(&wo, &wi, self.eta(hit_rec.front_face))
+    }
+}
+
+/**
+ * Microfacet normal connecting wo and wi, None if the pair can't be produced by any facet
+ */
+fn half_vector(wo: &Vec3, wi: &Vec3, eta: f32) -> Option<Vec3> {
+    if wo.z() <= 0.0 || wi.z() == 0.0 {
+        return None;
+    }
+
+    let is_reflection = wi.z() > 0.0;
+    let h = if is_reflection {
+        wo + wi
+    } else {
+        wo + wi * eta
+    };
+    if h.near_zero() {
+        return None;
+    }
+
+    let h = h.unit_vector();
+    let h = if h.z() < 0.0 { -h } else { h };
+
+    // Facet must face wo, and wi must lie on the side matching the event
+    let wi_side = if is_reflection { 1.0 } else { -1.0 };
+    if wo.dot(&h) <= 0.0 || wi.dot(&h) * wi_side <= 0.0 {
+        return None;
+    }
+
+    Some(h)
+}
@@ -2,5 +2,8 @@ use crate::util::{color::Color, ray::Ray};
 
 pub struct ScatterRecord {
     pub scattered_ray: Ray,
+    /// Sample weight, BSDF * cos / pdf
     pub attenuation: Color,
+    /// Solid angle density of sampled direction. None for specular (delta) directions.
+    pub pdf: Option<f32>,
 }
@@ -1,2 +1,3 @@
+pub mod onb;
 pub mod random;
 pub mod vec3;
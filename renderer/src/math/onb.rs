@@ -0,0 +1,45 @@
+use super::vec3::Vec3;
+
+/**
+ * Orthonormal basis around a surface normal. Local coordinates use z as the normal.
+ */
+pub struct Onb {
+    pub u: Vec3,
+    pub v: Vec3,
+    pub w: Vec3,
+}
+
+impl Onb {
+    /**
+     * Builds basis from unit vector (Duff et al. 2017, branchless)
+     */
+    pub fn from_w(w: &Vec3) -> Self {
+        let sign = 1.0_f32.copysign(w.z());
+        let a = -1.0 / (sign + w.z());
+        let b = w.x() * w.y() * a;
+
+        let u = Vec3::new([1.0 + sign * w.x() * w.x() * a, sign * b, -sign * w.x()]);
+        let v = Vec3::new([b, sign + w.y() * w.y() * a, -w.y()]);
+
+        Self { u, v, w: *w }
+    }
+
+    /**
+     * Builds basis with u aligned to surface tangent. Tangent must be perpendicular to normal.
+     */
+    pub fn from_normal_tangent(normal: &Vec3, tangent: &Vec3) -> Self {
+        Self {
+            u: *tangent,
+            v: normal.cross(tangent),
+            w: *normal,
+        }
+    }
+
+    pub fn to_local(&self, vec: &Vec3) -> Vec3 {
+        Vec3::new([vec.dot(&self.u), vec.dot(&self.v), vec.dot(&self.w)])
+    }
+
+    pub fn to_world(&self, vec: &Vec3) -> Vec3 {
+        self.u * vec.x() + self.v * vec.y() + self.w * vec.z()
+    }
+}
@@ -1,17 +1,29 @@
+use std::cell::RefCell;
+
 use super::vec3::Vec3;
-use rand::{thread_rng, Rng};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+thread_local! {
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
 
 pub fn rand_f32() -> f32 {
-    let mut rng = thread_rng();
-    rng.gen()
+    RNG.with(|rng| rng.borrow_mut().gen())
 }
 
 /**
  * Inclusive clamping
  */
 pub fn rand_f32_clamped(min: f32, max: f32) -> f32 {
-    let mut rng = thread_rng();
-    rng.gen_range(min..=max)
+    RNG.with(|rng| rng.borrow_mut().gen_range(min..=max))
+}
+
+/**
+ * Restarts random sequence of the calling thread. Reseeding before each unit of work makes
+ * results independent of which thread does the work.
+ */
+pub fn reseed(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
 }
 
 pub trait Random {
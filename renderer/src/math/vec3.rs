@@ -1,6 +1,9 @@
-use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::{
+    f32::consts::PI,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
 
-use super::random::{rand_f32_clamped, Random};
+use super::random::{rand_f32, rand_f32_clamped, Random};
 
 #[derive(Debug, Copy, Clone)]
 pub struct Vec3 {
@@ -88,6 +91,17 @@ impl Vec3 {
 
         -in_unit_sphere
     }
+
+    /**
+     * Cosine weighted direction around +Z axis
+     */
+    pub fn random_cosine_direction() -> Vec3 {
+        let r1 = rand_f32();
+        let phi = 2.0 * PI * rand_f32();
+        let r = r1.sqrt();
+
+        Vec3::new([r * phi.cos(), r * phi.sin(), (1.0 - r1).sqrt()])
+    }
 }
 
 impl Default for Vec3 {
@@ -0,0 +1,3 @@
+pub mod exr;
+pub mod pfm;
+pub mod y4m;
@@ -0,0 +1,107 @@
+use std::{fs::File, io::Write};
+
+use shared::data::Frame;
+
+const PIXEL_TYPE_FLOAT: i32 = 2;
+
+/**
+ * Writes beauty image and all AOVs to a single uncompressed scanline OpenEXR file.
+ * Beauty goes to R, G, B and A channels, AOVs to layers named "<aov>.<channel>".
+ */
+pub fn write_frame(file: &str, frame: &Frame) -> std::io::Result<()> {
+    let width = frame.width as usize;
+    let height = frame.height as usize;
+
+    // Planar channel data. Color keeps the background seen past the geometry, alpha is
+    // the coverage of geometry.
+    let mut channels: Vec<(String, Vec<f32>)> = vec![
+        ("R".to_string(), frame.pixels.iter().map(|p| p.r).collect()),
+        ("G".to_string(), frame.pixels.iter().map(|p| p.g).collect()),
+        ("B".to_string(), frame.pixels.iter().map(|p| p.b).collect()),
+        ("A".to_string(), frame.pixels.iter().map(|p| p.a).collect()),
+    ];
+    for aov in &frame.aovs {
+        for (channel, channel_name) in aov.channels.iter().enumerate() {
+            let values = (0..width * height)
+                .map(|index| aov.pixel(index)[channel])
+                .collect();
+            channels.push((format!("{}.{}", aov.name, channel_name), values));
+        }
+    }
+    // EXR requires channels in alphabetical order
+    channels.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut header = vec![];
+    header.extend(20000630_u32.to_le_bytes()); // Magic number
+    header.extend(2_u32.to_le_bytes());
+
+    let mut channel_list = vec![];
+    for (name, _) in &channels {
+        channel_list.extend(name.as_bytes());
+        channel_list.push(0);
+        channel_list.extend(PIXEL_TYPE_FLOAT.to_le_bytes());
+        channel_list.extend([0, 0, 0, 0]); // pLinear and reserved
+        channel_list.extend(1_i32.to_le_bytes()); // x sampling
+        channel_list.extend(1_i32.to_le_bytes()); // y sampling
+    }
+    channel_list.push(0);
+
+    let window: Vec<u8> = [0, 0, width as i32 - 1, height as i32 - 1]
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect();
+
+    write_attribute(&mut header, "channels", "chlist", &channel_list);
+    write_attribute(&mut header, "compression", "compression", &[0]);
+    write_attribute(&mut header, "dataWindow", "box2i", &window);
+    write_attribute(&mut header, "displayWindow", "box2i", &window);
+    write_attribute(&mut header, "lineOrder", "lineOrder", &[0]);
+    write_attribute(
+        &mut header,
+        "pixelAspectRatio",
+        "float",
+        &1.0_f32.to_le_bytes(),
+    );
+    write_attribute(&mut header, "screenWindowCenter", "v2f", &[0; 8]);
+    write_attribute(
+        &mut header,
+        "screenWindowWidth",
+        "float",
+        &1.0_f32.to_le_bytes(),
+    );
+    header.push(0);
+
+    // One scanline per block, offsets table points to each block
+    let line_size = width * channels.len() * 4;
+    let block_size = 8 + line_size;
+    let first_block = header.len() + height * 8;
+
+    let mut data = header;
+    for line in 0..height {
+        data.extend(((first_block + line * block_size) as u64).to_le_bytes());
+    }
+
+    for line in 0..height {
+        data.extend((line as i32).to_le_bytes());
+        data.extend((line_size as i32).to_le_bytes());
+
+        // EXR rows go from top to bottom, Frame rows from bottom to top
+        let row_start = (height - 1 - line) * width;
+        for (_, values) in &channels {
+            for value in &values[row_start..row_start + width] {
+                data.extend(value.to_le_bytes());
+            }
+        }
+    }
+
+    File::create(file)?.write_all(&data)
+}
+
+fn write_attribute(header: &mut Vec<u8>, name: &str, attribute_type: &str, value: &[u8]) {
+    header.extend(name.as_bytes());
+    header.push(0);
+    header.extend(attribute_type.as_bytes());
+    header.push(0);
+    header.extend((value.len() as i32).to_le_bytes());
+    header.extend(value);
+}
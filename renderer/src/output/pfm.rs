@@ -0,0 +1,25 @@
+use std::{fs::File, io::Write};
+
+use shared::data::Aov;
+
+/**
+ * Writes AOV as Portable Float Map. One channel AOVs become grayscale, others RGB with
+ * missing channels filled with zero. PFM rows go from bottom to top like Frame rows.
+ */
+pub fn write_aov(file: &str, aov: &Aov, width: i32, height: i32) -> std::io::Result<()> {
+    let (magic, channels) = match aov.channels.len() {
+        1 => ("Pf", 1),
+        _ => ("PF", 3),
+    };
+
+    let mut data = format!("{magic}\n{width} {height}\n-1.0\n").into_bytes();
+    for index in 0..(width * height) as usize {
+        let pixel = aov.pixel(index);
+        for channel in 0..channels {
+            let value = pixel.get(channel).copied().unwrap_or_default();
+            data.extend(value.to_le_bytes());
+        }
+    }
+
+    File::create(file)?.write_all(&data)
+}
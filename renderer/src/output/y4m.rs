@@ -0,0 +1,66 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Error, ErrorKind, Write},
+};
+
+use shared::data::{linear_to_srgb, Frame, Pixel};
+
+/**
+ * Uncompressed YUV4MPEG2 video stream, frames are added one by one as they are rendered.
+ * Uses full resolution chroma (4:4:4) and BT.601 limited range like most Y4M tools expect.
+ */
+pub struct Y4mWriter {
+    out: BufWriter<File>,
+    width: i32,
+    height: i32,
+}
+
+impl Y4mWriter {
+    pub fn create(file: &str, width: i32, height: i32, fps: u32) -> std::io::Result<Self> {
+        let mut out = BufWriter::new(File::create(file)?);
+        writeln!(out, "YUV4MPEG2 W{width} H{height} F{fps}:1 Ip A1:1 C444")?;
+        Ok(Self { out, width, height })
+    }
+
+    pub fn write_frame(&mut self, frame: &Frame) -> std::io::Result<()> {
+        if frame.width != self.width || frame.height != self.height {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "frame of {}x{} in a {}x{} video",
+                    frame.width, frame.height, self.width, self.height
+                ),
+            ));
+        }
+
+        // Planes are stored top to bottom and hold sRGB encoded color
+        let encoded: Vec<Pixel> = frame
+            .rows()
+            .flatten()
+            .map(|p| Pixel {
+                r: linear_to_srgb(p.r),
+                g: linear_to_srgb(p.g),
+                b: linear_to_srgb(p.b),
+                ..*p
+            })
+            .collect();
+        let plane = |component: fn(&Pixel) -> f32| -> Vec<u8> {
+            encoded
+                .iter()
+                .map(|pixel| component(pixel).round().clamp(0.0, 255.0) as u8)
+                .collect()
+        };
+
+        self.out.write_all(b"FRAME\n")?;
+        self.out.write_all(&plane(|p| {
+            16.0 + 219.0 * (0.299 * p.r + 0.587 * p.g + 0.114 * p.b)
+        }))?;
+        self.out.write_all(&plane(|p| {
+            128.0 + 224.0 * (-0.168736 * p.r - 0.331264 * p.g + 0.5 * p.b)
+        }))?;
+        self.out.write_all(&plane(|p| {
+            128.0 + 224.0 * (0.5 * p.r - 0.418688 * p.g - 0.081312 * p.b)
+        }))?;
+        self.out.flush()
+    }
+}
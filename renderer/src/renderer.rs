@@ -1 +1,10 @@
+pub mod aov;
+pub mod checkpoint;
+pub mod denoise;
+mod film;
+pub mod filter;
+mod integrator;
+mod region;
 pub mod renderer;
+pub mod settings;
+pub mod stats;
@@ -0,0 +1,152 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::{math::vec3::Vec3, util::color::Color};
+
+use super::integrator::PathSample;
+
+/**
+ * Render passes which can be produced next to the beauty image
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AovType {
+    /// Distance from camera to first hit
+    Depth,
+    /// World space shading normal of first hit
+    Normal,
+    Albedo,
+    ObjectId,
+    MaterialId,
+    Uv,
+    /// World space position of first hit
+    Position,
+    /// Light reaching camera after one bounce
+    Direct,
+    /// Light reaching camera after two or more bounces
+    Indirect,
+    SampleCount,
+}
+
+impl AovType {
+    pub const ALL: [AovType; 10] = [
+        AovType::Depth,
+        AovType::Normal,
+        AovType::Albedo,
+        AovType::ObjectId,
+        AovType::MaterialId,
+        AovType::Uv,
+        AovType::Position,
+        AovType::Direct,
+        AovType::Indirect,
+        AovType::SampleCount,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AovType::Depth => "depth",
+            AovType::Normal => "normal",
+            AovType::Albedo => "albedo",
+            AovType::ObjectId => "object_id",
+            AovType::MaterialId => "material_id",
+            AovType::Uv => "uv",
+            AovType::Position => "position",
+            AovType::Direct => "direct",
+            AovType::Indirect => "indirect",
+            AovType::SampleCount => "sample_count",
+        }
+    }
+
+    pub fn channels(&self) -> &'static [&'static str] {
+        match self {
+            AovType::Depth => &["Z"],
+            AovType::Normal | AovType::Position => &["X", "Y", "Z"],
+            AovType::Albedo | AovType::Direct | AovType::Indirect => &["R", "G", "B"],
+            AovType::ObjectId | AovType::MaterialId => &["id"],
+            AovType::Uv => &["U", "V"],
+            AovType::SampleCount => &["count"],
+        }
+    }
+}
+
+impl Display for AovType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for AovType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AovType::ALL
+            .into_iter()
+            .find(|aov| aov.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = AovType::ALL.iter().map(|aov| aov.name()).collect();
+                format!("unknown AOV '{}', expected one of: {}", s, names.join(", "))
+            })
+    }
+}
+
+/**
+ * Collects AOV values over all samples of a single pixel. Surface passes are averaged over
+ * samples that hit geometry, IDs come from the first such sample. Pixels without any hit
+ * get zeros and ID -1.
+ */
+#[derive(Default)]
+pub struct AovAccumulator {
+    samples: usize,
+    hits: usize,
+    depth: f32,
+    normal: Vec3,
+    albedo: Color,
+    uv: (f32, f32),
+    position: Vec3,
+    direct: Color,
+    indirect: Color,
+    ids: Option<(usize, usize)>,
+}
+
+impl AovAccumulator {
+    pub fn add(&mut self, sample: &PathSample) {
+        self.samples += 1;
+        self.direct = self.direct + sample.direct;
+        self.indirect = self.indirect + sample.indirect;
+
+        let Some(hit) = &sample.first_hit else {
+            return;
+        };
+
+        self.hits += 1;
+        self.depth += hit.depth;
+        self.normal = self.normal + hit.normal;
+        self.albedo = self.albedo + hit.albedo;
+        self.uv = (self.uv.0 + hit.uv.0, self.uv.1 + hit.uv.1);
+        self.position = self.position + hit.position;
+        self.ids.get_or_insert((hit.object_id, hit.material_id));
+    }
+
+    pub fn write(&self, aov: AovType, out: &mut Vec<f32>) {
+        let hits = self.hits.max(1) as f32;
+        let samples = self.samples.max(1) as f32;
+        let id = |id: Option<usize>| id.map_or(-1.0, |id| id as f32);
+
+        match aov {
+            AovType::Depth => out.push(self.depth / hits),
+            AovType::Normal => {
+                let normal = match self.normal.near_zero() {
+                    true => self.normal,
+                    false => self.normal.unit_vector(),
+                };
+                out.extend(normal.e)
+            }
+            AovType::Albedo => out.extend((self.albedo / hits).e),
+            AovType::ObjectId => out.push(id(self.ids.map(|ids| ids.0))),
+            AovType::MaterialId => out.push(id(self.ids.map(|ids| ids.1))),
+            AovType::Uv => out.extend([self.uv.0 / hits, self.uv.1 / hits]),
+            AovType::Position => out.extend((self.position / hits).e),
+            AovType::Direct => out.extend((self.direct / samples).e),
+            AovType::Indirect => out.extend((self.indirect / samples).e),
+            AovType::SampleCount => out.push(self.samples as f32),
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use shared::error::RenderError;
+
+use super::{film::Film, filter::FilterType};
+
+const MAGIC: &[u8; 4] = b"RRCK";
+const VERSION: u32 = 1;
+
+/**
+ * Samples accumulated by earlier renders of a frame. Rendering with a checkpoint adds new
+ * samples to the ones it holds, so a render can be continued later or one area refined
+ * with a crop window. Only the beauty image is kept, AOVs come from the latest render.
+ */
+pub struct Checkpoint {
+    pub(super) film: Film,
+    /// Renders accumulated so far, each one continues the random sequence of the last
+    pub(super) passes: u32,
+}
+
+impl Checkpoint {
+    pub(super) fn new(width: i32, height: i32, filter: FilterType) -> Self {
+        Self {
+            film: Film::new(width, height, filter),
+            passes: 0,
+        }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.film.width()
+    }
+
+    pub fn height(&self) -> i32 {
+        self.film.height()
+    }
+
+    pub fn passes(&self) -> u32 {
+        self.passes
+    }
+
+    pub fn load(path: &Path) -> Result<Self, RenderError> {
+        let io_error =
+            |err: io::Error| RenderError::Io(format!("could not read {}: {}", path.display(), err));
+        let mut input = BufReader::new(File::open(path).map_err(io_error)?);
+
+        let mut header = [0; 20];
+        input.read_exact(&mut header).map_err(io_error)?;
+        let field = |index: usize| header[index * 4..index * 4 + 4].try_into().unwrap();
+        let (width, height) = (i32::from_le_bytes(field(2)), i32::from_le_bytes(field(3)));
+        if &header[..4] != MAGIC
+            || u32::from_le_bytes(field(1)) != VERSION
+            || width < 1
+            || height < 1
+        {
+            return Err(RenderError::Io(format!(
+                "{} is not a render checkpoint",
+                path.display()
+            )));
+        }
+
+        let film =
+            Film::read(&mut input, width, height, FilterType::default()).map_err(io_error)?;
+        Ok(Self {
+            film,
+            passes: u32::from_le_bytes(field(4)),
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), RenderError> {
+        let write = || -> io::Result<()> {
+            let mut out = BufWriter::new(File::create(path)?);
+            out.write_all(MAGIC)?;
+            out.write_all(&VERSION.to_le_bytes())?;
+            out.write_all(&self.width().to_le_bytes())?;
+            out.write_all(&self.height().to_le_bytes())?;
+            out.write_all(&self.passes.to_le_bytes())?;
+            self.film.write(&mut out)?;
+            out.flush()
+        };
+        write()
+            .map_err(|err| RenderError::Io(format!("could not write {}: {}", path.display(), err)))
+    }
+}
@@ -0,0 +1,163 @@
+pub mod atrous;
+pub mod bilateral;
+
+use std::{fmt::Display, str::FromStr};
+
+use shared::data::{Frame, Pixel};
+
+use crate::{math::vec3::Vec3, util::color::Color};
+
+use super::aov::AovType;
+
+/**
+ * Post-process filters for noisy low sample renders. Both run on the CPU and use the
+ * albedo, normal and depth AOVs as edge stopping guides.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DenoiseMethod {
+    /// Joint bilateral filter over a fixed window
+    Bilateral,
+    /// SVGF style edge avoiding à-trous wavelet filter
+    Atrous,
+}
+
+impl DenoiseMethod {
+    pub const ALL: [DenoiseMethod; 2] = [DenoiseMethod::Bilateral, DenoiseMethod::Atrous];
+
+    /**
+     * AOVs the renderer has to produce for the filter, removed from frame afterwards unless requested
+     */
+    pub const GUIDE_AOVS: [AovType; 3] = [AovType::Albedo, AovType::Normal, AovType::Depth];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DenoiseMethod::Bilateral => "bilateral",
+            DenoiseMethod::Atrous => "atrous",
+        }
+    }
+
+    /**
+     * Returns denoised beauty pixels of the frame, alpha is kept
+     */
+    pub fn apply(&self, frame: &Frame) -> Vec<Pixel> {
+        let image = GuidedImage::from_frame(frame);
+        let filtered = match self {
+            DenoiseMethod::Bilateral => bilateral::filter(&image, &Default::default()),
+            DenoiseMethod::Atrous => atrous::filter(&image, &Default::default()),
+        };
+
+        filtered
+            .iter()
+            .zip(&frame.pixels)
+            .map(|(color, pixel)| Pixel::new(color.x(), color.y(), color.z(), pixel.a))
+            .collect()
+    }
+}
+
+impl Display for DenoiseMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for DenoiseMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DenoiseMethod::ALL
+            .into_iter()
+            .find(|method| method.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = DenoiseMethod::ALL.iter().map(|m| m.name()).collect();
+                format!(
+                    "unknown denoiser '{}', expected one of: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/**
+ * Linear beauty image with per pixel guides. Missing guide AOVs fall back to values
+ * which never stop edges.
+ */
+struct GuidedImage {
+    width: usize,
+    height: usize,
+    color: Vec<Color>,
+    albedo: Vec<Color>,
+    /// Zero for pixels without a hit
+    normal: Vec<Vec3>,
+    /// Zero for pixels without a hit
+    depth: Vec<f32>,
+}
+
+impl GuidedImage {
+    fn from_frame(frame: &Frame) -> Self {
+        let count = frame.pixels.len();
+        let vectors = |name: &str| -> Vec<Vec3> {
+            match frame.aov(name) {
+                Some(aov) => (0..count)
+                    .map(|i| Vec3::new(aov.pixel(i).try_into().unwrap()))
+                    .collect(),
+                None => vec![Vec3::default(); count],
+            }
+        };
+        let depth = match frame.aov(AovType::Depth.name()) {
+            Some(aov) => aov.values.clone(),
+            None => vec![0.0; count],
+        };
+
+        Self {
+            width: frame.width as usize,
+            height: frame.height as usize,
+            color: frame
+                .pixels
+                .iter()
+                .map(|p| Color::new([p.r, p.g, p.b]))
+                .collect(),
+            albedo: vectors(AovType::Albedo.name()),
+            normal: vectors(AovType::Normal.name()),
+            depth,
+        }
+    }
+
+    /**
+     * Index of pixel offset by (dx, dy), None when outside the image
+     */
+    fn neighbour(&self, index: usize, dx: i32, dy: i32) -> Option<usize> {
+        let x = (index % self.width) as i32 + dx;
+        let y = (index / self.width) as i32 + dy;
+        match x >= 0 && y >= 0 && x < self.width as i32 && y < self.height as i32 {
+            true => Some(y as usize * self.width + x as usize),
+            false => None,
+        }
+    }
+
+    /**
+     * Edge stopping weight from normals, 1 when both pixels missed the scene
+     */
+    fn normal_weight(&self, p: usize, q: usize, power: f32) -> f32 {
+        let (np, nq) = (&self.normal[p], &self.normal[q]);
+        match (np.near_zero(), nq.near_zero()) {
+            (true, true) => 1.0,
+            (false, false) => np.dot(nq).max(0.0).powf(power),
+            _ => 0.0,
+        }
+    }
+
+    /**
+     * Edge stopping weight from depth. Tolerance grows with screen distance and depth so
+     * slanted surfaces are not cut into bands.
+     */
+    fn depth_weight(&self, p: usize, q: usize, pixel_distance: f32, sigma: f32) -> f32 {
+        let (zp, zq) = (self.depth[p], self.depth[q]);
+        let tolerance = sigma * pixel_distance * zp.max(zq) / self.width as f32;
+        (-(zp - zq).abs() / (tolerance + 1e-4)).exp()
+    }
+}
+
+fn luminance(color: &Color) -> f32 {
+    0.2126 * color.x() + 0.7152 * color.y() + 0.0722 * color.z()
+}
@@ -0,0 +1,142 @@
+use rayon::prelude::*;
+
+use crate::util::color::Color;
+
+use super::{luminance, GuidedImage};
+
+/**
+ * B3 spline kernel, applied with growing gaps between taps on each iteration
+ */
+const KERNEL: [f32; 5] = [1.0 / 16.0, 1.0 / 4.0, 3.0 / 8.0, 1.0 / 4.0, 1.0 / 16.0];
+
+pub struct AtrousParams {
+    /// Each iteration doubles the step size, 5 iterations cover a 61 pixel footprint
+    pub iterations: u32,
+    /// Luminance tolerance in standard deviations of the local noise
+    pub sigma_luminance: f32,
+    /// Exponent of the normal dot product
+    pub normal_power: f32,
+    pub sigma_depth: f32,
+}
+
+impl Default for AtrousParams {
+    fn default() -> Self {
+        Self {
+            iterations: 5,
+            sigma_luminance: 4.0,
+            normal_power: 128.0,
+            sigma_depth: 4.0,
+        }
+    }
+}
+
+/**
+ * Edge avoiding à-trous wavelet filter as used by SVGF (Schied et al. 2017). Illumination is
+ * demodulated by albedo so texture detail survives, and the luminance edge stopping function
+ * is driven by a filtered estimate of per pixel variance.
+ */
+pub(super) fn filter(image: &GuidedImage, params: &AtrousParams) -> Vec<Color> {
+    let albedo: Vec<Color> = image.albedo.iter().map(demodulation_albedo).collect();
+    let mut illumination: Vec<Color> = image
+        .color
+        .iter()
+        .zip(&albedo)
+        .map(|(color, albedo)| color / albedo)
+        .collect();
+    let mut variance = spatial_variance(image, &illumination);
+
+    for iteration in 0..params.iterations {
+        let step = 1 << iteration;
+        (illumination, variance) = filter_step(image, &illumination, &variance, step, params);
+    }
+
+    illumination
+        .iter()
+        .zip(&albedo)
+        .map(|(illumination, albedo)| illumination * albedo)
+        .collect()
+}
+
+fn filter_step(
+    image: &GuidedImage,
+    illumination: &[Color],
+    variance: &[f32],
+    step: i32,
+    params: &AtrousParams,
+) -> (Vec<Color>, Vec<f32>) {
+    (0..illumination.len())
+        .into_par_iter()
+        .map(|p| {
+            let luminance_p = luminance(&illumination[p]);
+            let luminance_scale = params.sigma_luminance * variance[p].sqrt() + 1e-4;
+
+            let mut sum = Color::default();
+            let mut variance_sum = 0.0;
+            let mut weight_sum = 0.0;
+
+            for (ky, kernel_y) in KERNEL.iter().enumerate() {
+                for (kx, kernel_x) in KERNEL.iter().enumerate() {
+                    let (dx, dy) = ((kx as i32 - 2) * step, (ky as i32 - 2) * step);
+                    let Some(q) = image.neighbour(p, dx, dy) else {
+                        continue;
+                    };
+
+                    let pixel_distance = ((dx * dx + dy * dy) as f32).sqrt();
+                    let luminance_distance = (luminance(&illumination[q]) - luminance_p).abs();
+
+                    let weight = kernel_x
+                        * kernel_y
+                        * (-luminance_distance / luminance_scale).exp()
+                        * image.normal_weight(p, q, params.normal_power)
+                        * image.depth_weight(p, q, pixel_distance, params.sigma_depth);
+
+                    sum = sum + illumination[q] * weight;
+                    variance_sum += weight * weight * variance[q];
+                    weight_sum += weight;
+                }
+            }
+
+            (sum / weight_sum, variance_sum / (weight_sum * weight_sum))
+        })
+        .unzip()
+}
+
+/**
+ * Luminance variance of 3x3 neighbourhood, stands in for the temporal variance SVGF uses
+ */
+fn spatial_variance(image: &GuidedImage, illumination: &[Color]) -> Vec<f32> {
+    (0..illumination.len())
+        .into_par_iter()
+        .map(|p| {
+            let (mut sum, mut sum_squared, mut count) = (0.0, 0.0, 0.0);
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if let Some(q) = image.neighbour(p, dx, dy) {
+                        let luminance = luminance(&illumination[q]);
+                        sum += luminance;
+                        sum_squared += luminance * luminance;
+                        count += 1.0;
+                    }
+                }
+            }
+
+            let mean = sum / count;
+            (sum_squared / count - mean * mean).max(0.0)
+        })
+        .collect()
+}
+
+/**
+ * Albedo to divide illumination by. Pixels without a surface (sky) are not demodulated.
+ */
+fn demodulation_albedo(albedo: &Color) -> Color {
+    if albedo.near_zero() {
+        return Color::new([1.0, 1.0, 1.0]);
+    }
+
+    Color::new([
+        albedo.x().max(0.01),
+        albedo.y().max(0.01),
+        albedo.z().max(0.01),
+    ])
+}
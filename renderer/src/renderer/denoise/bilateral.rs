@@ -0,0 +1,72 @@
+use rayon::prelude::*;
+
+use crate::util::color::Color;
+
+use super::{luminance, GuidedImage};
+
+pub struct BilateralParams {
+    /// Window half size in pixels
+    pub radius: i32,
+    pub sigma_spatial: f32,
+    /// Relative to the center pixel luminance
+    pub sigma_color: f32,
+    pub sigma_albedo: f32,
+    /// Exponent of the normal dot product
+    pub normal_power: f32,
+    pub sigma_depth: f32,
+}
+
+impl Default for BilateralParams {
+    fn default() -> Self {
+        Self {
+            radius: 5,
+            sigma_spatial: 3.0,
+            sigma_color: 1.0,
+            sigma_albedo: 0.1,
+            normal_power: 64.0,
+            sigma_depth: 4.0,
+        }
+    }
+}
+
+/**
+ * Joint bilateral filter. Neighbours are weighted by screen distance, color difference and
+ * difference of the guide buffers.
+ */
+pub(super) fn filter(image: &GuidedImage, params: &BilateralParams) -> Vec<Color> {
+    (0..image.color.len())
+        .into_par_iter()
+        .map(|p| {
+            let center = &image.color[p];
+            let color_scale = params.sigma_color * luminance(center).max(0.05);
+            let mut sum = Color::default();
+            let mut weight_sum = 0.0;
+
+            for dy in -params.radius..=params.radius {
+                for dx in -params.radius..=params.radius {
+                    let Some(q) = image.neighbour(p, dx, dy) else {
+                        continue;
+                    };
+
+                    let distance_squared = (dx * dx + dy * dy) as f32;
+                    let color_distance = (image.color[q] - center).length_squared();
+                    let albedo_distance = (image.albedo[q] - image.albedo[p]).length_squared();
+
+                    let weight = (-distance_squared
+                        / (2.0 * params.sigma_spatial * params.sigma_spatial)
+                        - color_distance / (2.0 * color_scale * color_scale)
+                        - albedo_distance / (2.0 * params.sigma_albedo * params.sigma_albedo))
+                        .exp()
+                        * image.normal_weight(p, q, params.normal_power)
+                        * image.depth_weight(p, q, distance_squared.sqrt(), params.sigma_depth);
+
+                    sum = sum + image.color[q] * weight;
+                    weight_sum += weight;
+                }
+            }
+
+            // Center pixel always has weight 1 so the sum is never zero
+            sum / weight_sum
+        })
+        .collect()
+}
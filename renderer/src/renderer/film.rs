@@ -0,0 +1,180 @@
+use std::io::{self, Read, Write};
+
+use crate::util::color::Color;
+
+use super::filter::FilterType;
+
+#[derive(Clone, Copy, Default)]
+struct FilmPixel {
+    weighted_color: Color,
+    weighted_alpha: f32,
+    weight: f32,
+}
+
+/**
+ * Accumulates samples into pixels. Each sample is splatted to every pixel whose center is
+ * inside the filter radius, final pixel value is the weighted average of its samples.
+ * A film can cover only a band of rows so bands can be rendered in parallel and merged.
+ */
+pub struct Film {
+    width: i32,
+    height: i32,
+    filter: FilterType,
+    /// First row covered by this film
+    y_min: i32,
+    rows: i32,
+    pixels: Vec<FilmPixel>,
+}
+
+impl Film {
+    pub fn new(width: i32, height: i32, filter: FilterType) -> Self {
+        Self::band(width, height, filter, 0, height)
+    }
+
+    fn band(width: i32, height: i32, filter: FilterType, y_min: i32, y_max: i32) -> Self {
+        let rows = y_max - y_min;
+        Self {
+            width,
+            height,
+            filter,
+            y_min,
+            rows,
+            pixels: vec![FilmPixel::default(); (width * rows) as usize],
+        }
+    }
+
+    /**
+     * Same samples splatted with another filter from now on
+     */
+    pub fn with_filter(self, filter: FilterType) -> Self {
+        Self { filter, ..self }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /**
+     * Empty film covering rows which samples taken from rows [y_start, y_end) can reach
+     */
+    pub fn tile(&self, y_start: i32, y_end: i32) -> Self {
+        let reach = self.filter.radius().ceil() as i32;
+        let y_min = (y_start - reach).max(0);
+        let y_max = (y_end + reach).min(self.height);
+        Self::band(self.width, self.height, self.filter, y_min, y_max)
+    }
+
+    /**
+     * Adds sample at continuous raster position, pixel (x, y) covers [x, x + 1) x [y, y + 1).
+     * Alpha is 1 for samples which hit geometry and 0 for background.
+     */
+    pub fn add_sample(&mut self, x: f32, y: f32, color: &Color, alpha: f32) {
+        let radius = self.filter.radius();
+        let x_range = (x - 0.5 - radius).ceil().max(0.0) as i32
+            ..=((x - 0.5 + radius).floor() as i32).min(self.width - 1);
+        let y_range = ((y - 0.5 - radius).ceil() as i32).max(self.y_min)
+            ..=((y - 0.5 + radius).floor() as i32).min(self.y_min + self.rows - 1);
+
+        for pixel_y in y_range {
+            for pixel_x in x_range.clone() {
+                let weight = self
+                    .filter
+                    .evaluate(pixel_x as f32 + 0.5 - x, pixel_y as f32 + 0.5 - y);
+                if weight == 0.0 {
+                    continue;
+                }
+
+                let index = self.index(pixel_x, pixel_y);
+                let pixel = &mut self.pixels[index];
+                pixel.weighted_color = pixel.weighted_color + color * weight;
+                pixel.weighted_alpha += alpha * weight;
+                pixel.weight += weight;
+            }
+        }
+    }
+
+    /**
+     * Adds samples of a tile created with Film::tile
+     */
+    pub fn merge(&mut self, tile: &Film) {
+        let offset = self.index(0, tile.y_min);
+        for (pixel, tile_pixel) in self.pixels[offset..].iter_mut().zip(&tile.pixels) {
+            pixel.weighted_color = pixel.weighted_color + tile_pixel.weighted_color;
+            pixel.weighted_alpha += tile_pixel.weighted_alpha;
+            pixel.weight += tile_pixel.weight;
+        }
+    }
+
+    /**
+     * Weighted average of samples reaching pixel. Negative filter lobes can make the
+     * weight non-positive with few samples, those pixels are black.
+     */
+    pub fn pixel(&self, x: i32, y: i32) -> Color {
+        let pixel = &self.pixels[self.index(x, y)];
+        match pixel.weight > 0.0 {
+            true => pixel.weighted_color / pixel.weight,
+            false => Color::default(),
+        }
+    }
+
+    /**
+     * Weighted coverage of pixel in [0, 1], 0 for pixels without positive weight
+     */
+    pub fn alpha(&self, x: i32, y: i32) -> f32 {
+        let pixel = &self.pixels[self.index(x, y)];
+        match pixel.weight > 0.0 {
+            true => (pixel.weighted_alpha / pixel.weight).clamp(0.0, 1.0),
+            false => 0.0,
+        }
+    }
+
+    /**
+     * Writes accumulated sums of every pixel as little endian floats
+     */
+    pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        for pixel in &self.pixels {
+            let color = &pixel.weighted_color;
+            for value in [
+                color.x(),
+                color.y(),
+                color.z(),
+                pixel.weighted_alpha,
+                pixel.weight,
+            ] {
+                out.write_all(&value.to_le_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * Reads sums written with Film::write for a film of given size
+     */
+    pub fn read(
+        input: &mut impl Read,
+        width: i32,
+        height: i32,
+        filter: FilterType,
+    ) -> io::Result<Self> {
+        let mut film = Self::new(width, height, filter);
+        let mut bytes = [0; 4];
+        let mut next = || -> io::Result<f32> {
+            input.read_exact(&mut bytes)?;
+            Ok(f32::from_le_bytes(bytes))
+        };
+        for pixel in &mut film.pixels {
+            pixel.weighted_color = Color::new([next()?, next()?, next()?]);
+            pixel.weighted_alpha = next()?;
+            pixel.weight = next()?;
+        }
+        Ok(film)
+    }
+
+    fn index(&self, x: i32, y: i32) -> usize {
+        ((y - self.y_min) * self.width + x) as usize
+    }
+}
@@ -0,0 +1,140 @@
+use std::{f32::consts::PI, fmt::Display, str::FromStr};
+
+/**
+ * Pixel reconstruction filter. Filters are separable and evaluated with the offset from
+ * pixel center to sample position, in pixels.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FilterType {
+    /// Equal weight inside the radius, radius 0.5 keeps every sample inside its own pixel
+    Box { radius: f32 },
+    /// Linear falloff to zero at the radius
+    Tent { radius: f32 },
+    /// Gaussian shifted down so it reaches zero at the radius
+    Gaussian { radius: f32, sigma: f32 },
+    /// Mitchell-Netravali cubic, B = C = 1/3 is the recommended compromise between ringing and blur
+    Mitchell { radius: f32, b: f32, c: f32 },
+    /// Sinc windowed by a sinc stretched to the radius
+    Lanczos { radius: f32 },
+}
+
+impl Default for FilterType {
+    fn default() -> Self {
+        FilterType::Box { radius: 0.5 }
+    }
+}
+
+impl FilterType {
+    pub const NAMES: [&'static str; 5] = ["box", "tent", "gaussian", "mitchell", "lanczos"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FilterType::Box { .. } => "box",
+            FilterType::Tent { .. } => "tent",
+            FilterType::Gaussian { .. } => "gaussian",
+            FilterType::Mitchell { .. } => "mitchell",
+            FilterType::Lanczos { .. } => "lanczos",
+        }
+    }
+
+    pub fn radius(&self) -> f32 {
+        match *self {
+            FilterType::Box { radius }
+            | FilterType::Tent { radius }
+            | FilterType::Gaussian { radius, .. }
+            | FilterType::Mitchell { radius, .. }
+            | FilterType::Lanczos { radius } => radius,
+        }
+    }
+
+    /**
+     * Filter weight for offset (x, y). Mitchell and Lanczos have negative lobes.
+     */
+    pub fn evaluate(&self, x: f32, y: f32) -> f32 {
+        self.evaluate_1d(x) * self.evaluate_1d(y)
+    }
+
+    fn evaluate_1d(&self, x: f32) -> f32 {
+        match *self {
+            // Half open so a sample on the border between two pixels only lands in one
+            FilterType::Box { radius } => match -radius < x && x <= radius {
+                true => 1.0,
+                false => 0.0,
+            },
+            FilterType::Tent { radius } => (radius - x.abs()).max(0.0),
+            FilterType::Gaussian { radius, sigma } => {
+                let gaussian = |x: f32| (-x * x / (2.0 * sigma * sigma)).exp();
+                (gaussian(x) - gaussian(radius)).max(0.0)
+            }
+            FilterType::Mitchell { radius, b, c } => mitchell(2.0 * x / radius, b, c),
+            FilterType::Lanczos { radius } => match x.abs() < radius {
+                true => sinc(x) * sinc(x / radius),
+                false => 0.0,
+            },
+        }
+    }
+}
+
+impl Display for FilterType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/**
+ * Parses filter name, each filter gets its commonly used radius
+ */
+impl FromStr for FilterType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "box" => Ok(FilterType::Box { radius: 0.5 }),
+            "tent" => Ok(FilterType::Tent { radius: 1.0 }),
+            "gaussian" => Ok(FilterType::Gaussian {
+                radius: 1.5,
+                sigma: 0.5,
+            }),
+            "mitchell" => Ok(FilterType::Mitchell {
+                radius: 2.0,
+                b: 1.0 / 3.0,
+                c: 1.0 / 3.0,
+            }),
+            "lanczos" => Ok(FilterType::Lanczos { radius: 2.0 }),
+            _ => Err(format!(
+                "unknown filter '{}', expected one of: {}",
+                s,
+                FilterType::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/**
+ * Mitchell-Netravali cubic with support [-2, 2]
+ */
+fn mitchell(x: f32, b: f32, c: f32) -> f32 {
+    let x = x.abs();
+    let value = match x {
+        x if x < 1.0 => {
+            (12.0 - 9.0 * b - 6.0 * c) * x.powi(3)
+                + (-18.0 + 12.0 * b + 6.0 * c) * x.powi(2)
+                + (6.0 - 2.0 * b)
+        }
+        x if x < 2.0 => {
+            (-b - 6.0 * c) * x.powi(3)
+                + (6.0 * b + 30.0 * c) * x.powi(2)
+                + (-12.0 * b - 48.0 * c) * x
+                + (8.0 * b + 24.0 * c)
+        }
+        _ => 0.0,
+    };
+    value / 6.0
+}
+
+fn sinc(x: f32) -> f32 {
+    match x.abs() < 1e-5 {
+        true => 1.0,
+        false => (PI * x).sin() / (PI * x),
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{
+    materials::material::Material,
+    math::vec3::Vec3,
+    shapes::{hit_collection::HitCollection, hit_record::HitRecord},
+    spectral::{
+        sampled::{SampledSpectrum, SampledWavelengths},
+        upsampling::rgb_to_spectrum,
+    },
+    util::{color::Color, point::Point, ray::Ray},
+};
+
+use super::stats::TraceStats;
+
+/**
+ * Result of tracing a single camera ray
+ */
+pub struct PathSample {
+    pub color: Color,
+    /// Light reaching the camera after exactly one surface bounce
+    pub direct: Color,
+    /// Light reaching the camera after two or more surface bounces
+    pub indirect: Color,
+    pub first_hit: Option<SurfaceSample>,
+}
+
+/**
+ * Geometry and material information of the first surface seen by camera ray
+ */
+pub struct SurfaceSample {
+    pub depth: f32,
+    pub normal: Vec3,
+    pub albedo: Color,
+    pub object_id: usize,
+    pub material_id: usize,
+    pub uv: (f32, f32),
+    pub position: Point,
+}
+
+impl SurfaceSample {
+    fn new(ray: &Ray, hit: &HitRecord) -> Self {
+        Self {
+            depth: hit.t * ray.direction().length(),
+            normal: hit.normal,
+            albedo: hit.material.albedo(hit),
+            object_id: hit.object_id,
+            material_id: hit.material.id(),
+            uv: (hit.u, hit.v),
+            position: hit.point,
+        }
+    }
+}
+
+pub fn trace_rgb(
+    ray: Ray,
+    world: &HitCollection,
+    max_depth: i16,
+    stats: &mut TraceStats,
+) -> PathSample {
+    let mut ray = ray;
+    let mut throughput = Color::new([1.0, 1.0, 1.0]);
+    let mut first_hit = None;
+
+    for bounces in 0..max_depth {
+        let Some(hit) = stats.intersect(world, &ray, bounces) else {
+            let light = throughput * background(&ray);
+            return PathSample::from_light(light, bounces, first_hit);
+        };
+
+        if bounces == 0 {
+            first_hit = Some(SurfaceSample::new(&ray, &hit));
+        }
+
+        let Some(scatter) = stats.shade(|| hit.material.scatter(&ray, &hit)) else {
+            break;
+        };
+
+        throughput = throughput * scatter.attenuation;
+        ray = scatter.scattered_ray.with_time(ray.time());
+    }
+
+    PathSample::from_light(Color::default(), max_depth, first_hit)
+}
+
+pub fn trace_spectral(
+    ray: Ray,
+    world: &HitCollection,
+    max_depth: i16,
+    wavelengths: &mut SampledWavelengths,
+    stats: &mut TraceStats,
+) -> PathSample {
+    let mut ray = ray.with_wavelength(wavelengths.hero());
+    let mut throughput = SampledSpectrum::new(1.0);
+    let mut first_hit = None;
+
+    for bounces in 0..max_depth {
+        let Some(hit) = stats.intersect(world, &ray, bounces) else {
+            let radiance = throughput * rgb_to_spectrum(&background(&ray), wavelengths);
+            return PathSample::from_light(wavelengths.to_rgb(&radiance), bounces, first_hit);
+        };
+
+        if bounces == 0 {
+            first_hit = Some(SurfaceSample::new(&ray, &hit));
+        }
+
+        let Some(scatter) = stats.shade(|| hit.material.scatter(&ray, &hit)) else {
+            break;
+        };
+
+        // Wavelength dependent paths can't be shared with the other wavelengths
+        if hit.material.is_wavelength_dependent() {
+            wavelengths.terminate_secondary();
+        }
+
+        throughput = throughput * rgb_to_spectrum(&scatter.attenuation, wavelengths);
+        ray = scatter
+            .scattered_ray
+            .with_wavelength(wavelengths.hero())
+            .with_time(ray.time());
+    }
+
+    PathSample::from_light(Color::default(), max_depth, first_hit)
+}
+
+impl PathSample {
+    /**
+     * Sample for image position where camera produces no ray
+     */
+    pub fn empty() -> Self {
+        PathSample::from_light(Color::default(), 0, None)
+    }
+
+    /**
+     * Coverage of the sample, 1 when the camera ray hit geometry and 0 for background
+     */
+    pub fn alpha(&self) -> f32 {
+        match self.first_hit {
+            Some(_) => 1.0,
+            None => 0.0,
+        }
+    }
+
+    /**
+     * Scales light reaching the camera, surface information is kept
+     */
+    pub fn exposed(self, exposure: f32) -> Self {
+        Self {
+            color: self.color * exposure,
+            direct: self.direct * exposure,
+            indirect: self.indirect * exposure,
+            ..self
+        }
+    }
+
+    fn from_light(light: Color, bounces: i16, first_hit: Option<SurfaceSample>) -> Self {
+        let (direct, indirect) = match bounces {
+            0 => (Color::default(), Color::default()),
+            1 => (light, Color::default()),
+            _ => (Color::default(), light),
+        };
+
+        Self {
+            color: light,
+            direct,
+            indirect,
+            first_hit,
+        }
+    }
+}
+
+fn background(ray: &Ray) -> Color {
+    let unit_direction = ray.unit_direction();
+    let t = 0.5 * (unit_direction.y() + 1.0);
+    Color::new([1.0, 1.0, 1.0]) * (1.0 - t) + Color::new([0.5, 0.7, 1.0]) * t
+}
@@ -0,0 +1,66 @@
+use std::ops::Range;
+
+use shared::settings::CropWindow;
+
+/**
+ * Rectangle of pixels in film coordinates, rows counted from the bottom like Frame rows
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Region {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Region {
+    pub fn full(frame_width: i32, frame_height: i32) -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            width: frame_width,
+            height: frame_height,
+        }
+    }
+
+    /**
+     * Region of a crop window, which counts rows from the top
+     */
+    pub fn from_crop(crop: &CropWindow, frame_width: i32, frame_height: i32) -> Self {
+        let (columns, rows) = crop.pixel_ranges(frame_width, frame_height);
+        Self {
+            x: columns.start,
+            y: frame_height - rows.end,
+            width: columns.len() as i32,
+            height: rows.len() as i32,
+        }
+    }
+
+    pub fn columns(&self) -> Range<i32> {
+        self.x..self.x + self.width
+    }
+
+    pub fn rows(&self) -> Range<i32> {
+        self.y..self.y + self.height
+    }
+
+    pub fn len(&self) -> usize {
+        (self.width * self.height) as usize
+    }
+
+    pub fn is_full(&self, frame_width: i32, frame_height: i32) -> bool {
+        *self == Region::full(frame_width, frame_height)
+    }
+
+    /**
+     * Places values of the region pixels, `channels` per pixel in row order, into a frame
+     * sized buffer
+     */
+    pub fn paste<T: Copy>(&self, values: &[T], channels: usize, frame: &mut [T], frame_width: i32) {
+        let row_length = self.width as usize * channels;
+        for (row, region_row) in self.rows().zip(values.chunks(row_length)) {
+            let start = (row * frame_width + self.x) as usize * channels;
+            frame[start..start + row_length].copy_from_slice(region_row);
+        }
+    }
+}
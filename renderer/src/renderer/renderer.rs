@@ -1,105 +1,541 @@
+use std::time::Instant;
+
 use rayon::iter::IntoParallelIterator;
 
 use rayon::prelude::*;
-use shared::data::Pixel;
-use shared::traits::Render;
+use shared::{
+    data::{Aov, Frame, Pixel},
+    error::RenderError,
+    settings::{CropOutput, Integrator, RenderSettings},
+    traits::Render,
+};
 
 use crate::{
-    materials::material::Material,
-    math::random::rand_f32,
-    scene::scene::Scene,
-    shapes::{hit_collection::HitCollection, traits::Hit},
-    util::{
-        color::{sampled_value_to_normalized, Color},
-        ray::Ray,
+    math::random::{rand_f32, reseed},
+    scene::{
+        animation::ObjectAnimation,
+        camera::{CameraModel, CameraType},
+        camera_description::CameraDescription,
+        scene::Scene,
     },
+    spectral::sampled::SampledWavelengths,
+    util::color::Color,
+};
+
+use super::{
+    aov::{AovAccumulator, AovType},
+    checkpoint::Checkpoint,
+    denoise::DenoiseMethod,
+    film::Film,
+    filter::FilterType,
+    integrator::{trace_rgb, trace_spectral, PathSample},
+    region::Region,
+    settings::FilmSettings,
+    stats::{RenderStats, TraceStats},
 };
 
-const MAX_RECURSION_DEPTH: i16 = 50;
+/**
+ * Rows rendered per parallel task. Each tile also holds rows its samples splat into.
+ */
+const TILE_ROWS: i32 = 8;
+
+/**
+ * Film tile, AOV values of each pixel interleaved in order of passes and statistics
+ * of the rows it covers
+ */
+struct RenderedTile {
+    film: Film,
+    aov_values: Vec<Vec<f32>>,
+    stats: TraceStats,
+    /// Empty unless statistics are collected
+    pixel_cost: Vec<f32>,
+}
 
 pub struct Renderer {
     scene: Scene,
+    camera: CameraDescription,
+    /// Seconds from start of animation
+    time: f32,
+    film: FilmSettings,
 }
 
-impl Render for Renderer {
-    fn render(
+impl Default for Renderer {
+    fn default() -> Self {
+        Renderer::new(Scene::default(), FilmSettings::default())
+    }
+}
+
+impl Renderer {
+    /**
+     * Renders scene through its first camera, or the default camera when it has none
+     */
+    pub fn new(scene: Scene, film: FilmSettings) -> Self {
+        Self {
+            camera: scene.cameras.first().cloned().unwrap_or_default(),
+            scene,
+            time: 0.0,
+            film,
+        }
+    }
+
+    pub fn scene(&self) -> &Scene {
+        &self.scene
+    }
+
+    pub fn film(&self) -> &FilmSettings {
+        &self.film
+    }
+
+    /**
+     * Scene to render, camera is reset to the first camera of the scene or the default camera
+     */
+    pub fn with_scene(self, scene: Scene) -> Self {
+        Self {
+            camera: scene.cameras.first().cloned().unwrap_or_default(),
+            scene,
+            ..self
+        }
+    }
+
+    /**
+     * Camera to render through, projection is built for the aspect ratio of each rendered frame
+     */
+    pub fn with_camera(self, camera: CameraDescription) -> Self {
+        Self { camera, ..self }
+    }
+
+    /**
+     * Point in time of the animation to render
+     */
+    pub fn with_time(self, time: f32) -> Self {
+        Self { time, ..self }
+    }
+
+    /**
+     * Attaches keyframes to scene objects, fails when an animation refers to a missing object
+     */
+    pub fn with_object_animations(
+        mut self,
+        animations: &[ObjectAnimation],
+    ) -> Result<Self, String> {
+        self.scene.animate(animations)?;
+        Ok(self)
+    }
+
+    pub fn with_film(self, film: FilmSettings) -> Self {
+        Self { film, ..self }
+    }
+
+    /**
+     * Render passes to produce into Frame::aovs next to the beauty image
+     */
+    pub fn with_aovs(mut self, aovs: Vec<AovType>) -> Self {
+        self.film.aovs = aovs;
+        self
+    }
+
+    /**
+     * Filter applied to the beauty image after rendering
+     */
+    pub fn with_denoise(mut self, denoise: Option<DenoiseMethod>) -> Self {
+        self.film.denoise = denoise;
+        self
+    }
+
+    /**
+     * Reconstruction filter used to splat samples to pixels
+     */
+    pub fn with_filter(mut self, filter: FilterType) -> Self {
+        self.film.filter = filter;
+        self
+    }
+
+    /**
+     * Restarts random sequence of the calling thread for pixel when rendering with a seed.
+     * Same seed, scene and settings give the same image regardless of thread count.
+     */
+    fn reseed_pixel(&self, x: i32, y: i32, settings: &RenderSettings) {
+        if let Some(seed) = settings.seed {
+            let index = y as u64 * settings.width as u64 + x as u64;
+            let time = (self.time.to_bits() as u64) << 32;
+            reseed(seed ^ time ^ index.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+        }
+    }
+
+    /**
+     * Requested AOVs followed by guides the denoiser needs
+     */
+    fn passes(&self) -> Vec<AovType> {
+        let mut passes = self.film.aovs.clone();
+        if self.film.denoise.is_some() {
+            for guide in DenoiseMethod::GUIDE_AOVS {
+                if !passes.contains(&guide) {
+                    passes.push(guide);
+                }
+            }
+        }
+        passes
+    }
+
+    fn sample(
         &self,
-        frame_width: i32,
-        frame_height: i32,
-        samples_per_pixel: i16,
-    ) -> shared::data::Frame {
-        let pixels: Vec<Pixel> = (0..frame_height)
+        camera: &CameraType,
+        u: f32,
+        v: f32,
+        settings: &RenderSettings,
+        stats: &mut TraceStats,
+    ) -> PathSample {
+        let Some(ray) = camera.get_ray(u, v) else {
+            return PathSample::empty();
+        };
+        let ray = ray.with_time(self.time);
+
+        let sample = match settings.integrator {
+            Integrator::Rgb => trace_rgb(ray, &self.scene.world, settings.max_depth, stats),
+            Integrator::Spectral => {
+                let mut wavelengths = SampledWavelengths::sample_uniform(rand_f32());
+                trace_spectral(
+                    ray,
+                    &self.scene.world,
+                    settings.max_depth,
+                    &mut wavelengths,
+                    stats,
+                )
+            }
+        };
+
+        sample.exposed(camera.exposure())
+    }
+
+    /**
+     * Renders region of few rows into a film tile with the splatted samples
+     */
+    fn render_tile(
+        &self,
+        camera: &CameraType,
+        aovs: &[AovType],
+        film: &Film,
+        region: Region,
+        settings: &RenderSettings,
+        collect_stats: bool,
+    ) -> RenderedTile {
+        let (frame_width, frame_height) = (film.width(), film.height());
+        let mut tile = RenderedTile {
+            film: film.tile(region.rows().start, region.rows().end),
+            aov_values: vec![],
+            stats: TraceStats::new(collect_stats),
+            pixel_cost: vec![],
+        };
+
+        for y in region.rows() {
+            for x in region.columns() {
+                let start = collect_stats.then(Instant::now);
+                self.reseed_pixel(x, y, settings);
+                let mut accumulator = AovAccumulator::default();
+                for _ in 0..settings.samples_per_pixel {
+                    let sample_x = x as f32 + rand_f32();
+                    let sample_y = y as f32 + rand_f32();
+
+                    let sample = self.sample(
+                        camera,
+                        sample_x / (frame_width - 1) as f32,
+                        sample_y / (frame_height - 1) as f32,
+                        settings,
+                        &mut tile.stats,
+                    );
+                    tile.film
+                        .add_sample(sample_x, sample_y, &sample.color, sample.alpha());
+                    if !aovs.is_empty() {
+                        accumulator.add(&sample);
+                    }
+                }
+
+                let mut values = vec![];
+                for aov in aovs {
+                    accumulator.write(*aov, &mut values);
+                }
+                tile.aov_values.push(values);
+
+                if let Some(start) = start {
+                    tile.pixel_cost.push(start.elapsed().as_secs_f32());
+                }
+            }
+        }
+
+        tile
+    }
+
+    /**
+     * Renders frame and collects ray counts and timings. Timing every intersection slows
+     * rendering down a little, use Render::render when statistics are not needed.
+     */
+    pub fn render_with_stats(
+        &self,
+        settings: &RenderSettings,
+    ) -> Result<(Frame, RenderStats), RenderError> {
+        let (frame, stats, _) = self.render_frame(settings, None, true)?;
+        Ok((frame, stats))
+    }
+
+    /**
+     * Adds samples to a checkpoint of an earlier render of the same size, or to a new one when
+     * None. The frame shows every sample so far and with a crop window only that area gets new
+     * samples. Returned checkpoint can be saved to continue later.
+     */
+    pub fn render_resumed(
+        &self,
+        settings: &RenderSettings,
+        checkpoint: Option<Checkpoint>,
+    ) -> Result<(Frame, Checkpoint), RenderError> {
+        let (frame, _, checkpoint) = self.render_frame(settings, checkpoint, false)?;
+        Ok((frame, checkpoint))
+    }
+
+    fn render_frame(
+        &self,
+        settings: &RenderSettings,
+        checkpoint: Option<Checkpoint>,
+        collect_stats: bool,
+    ) -> Result<(Frame, RenderStats, Checkpoint), RenderError> {
+        settings.validate()?;
+        let checkpoint = match checkpoint {
+            Some(checkpoint)
+                if checkpoint.width() != settings.width
+                    || checkpoint.height() != settings.height =>
+            {
+                return Err(RenderError::InvalidSettings(format!(
+                    "checkpoint of a {}x{} frame can't be resumed at {}x{}",
+                    checkpoint.width(),
+                    checkpoint.height(),
+                    settings.width,
+                    settings.height
+                )));
+            }
+            Some(checkpoint) => checkpoint,
+            None => Checkpoint::new(settings.width, settings.height, self.film.filter),
+        };
+        let camera = self
+            .camera
+            .build(settings.width as f32 / settings.height as f32)?;
+
+        match settings.threads {
+            Some(threads) => rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .map_err(|err| RenderError::Unsupported(format!("thread pool: {}", err)))?
+                .install(|| {
+                    Ok(self.render_frame_parallel(&camera, settings, checkpoint, collect_stats))
+                }),
+            None => Ok(self.render_frame_parallel(&camera, settings, checkpoint, collect_stats)),
+        }
+    }
+
+    /**
+     * Renders validated settings on the current rayon thread pool
+     */
+    fn render_frame_parallel(
+        &self,
+        camera: &CameraType,
+        settings: &RenderSettings,
+        checkpoint: Checkpoint,
+        collect_stats: bool,
+    ) -> (Frame, RenderStats, Checkpoint) {
+        let (frame_width, frame_height) = (settings.width, settings.height);
+        let region = match &settings.crop {
+            Some(crop) => Region::from_crop(crop, frame_width, frame_height),
+            None => Region::full(frame_width, frame_height),
+        };
+        let start = Instant::now();
+        let passes = self.passes();
+        let pass = checkpoint.passes;
+        // Each resumed pass continues with a different random sequence
+        let pass_settings = RenderSettings {
+            seed: settings
+                .seed
+                .map(|seed| seed ^ (pass as u64).wrapping_mul(0xbf58_476d_1ce4_e5b9)),
+            ..settings.clone()
+        };
+        let film = checkpoint.film.with_filter(self.film.filter);
+        let tiles: Vec<RenderedTile> = region
+            .rows()
+            .step_by(TILE_ROWS as usize)
+            .collect::<Vec<i32>>()
             .into_par_iter()
-            .flat_map(|y| {
-                (0..frame_width).into_par_iter().map(move |x| {
-                    return self.render_pixel(x, y, frame_width, frame_height, samples_per_pixel);
-                })
+            .map(|y_start| {
+                let tile = Region {
+                    y: y_start,
+                    height: TILE_ROWS.min(region.rows().end - y_start),
+                    ..region
+                };
+                self.render_tile(camera, &passes, &film, tile, &pass_settings, collect_stats)
+            })
+            .collect();
+
+        let mut film = film;
+        let mut aov_values = Vec::with_capacity(region.len());
+        let mut stats = RenderStats {
+            frames: 1,
+            samples: region.len() as u64 * settings.samples_per_pixel as u64,
+            ..Default::default()
+        };
+        for tile in tiles {
+            film.merge(&tile.film);
+            aov_values.extend(tile.aov_values);
+            stats.trace.merge(&tile.stats);
+            stats.pixel_cost.extend(tile.pixel_cost);
+        }
+
+        let pixels = film_pixels(&film, &region);
+
+        // Split interleaved per pixel values into one layer per AOV
+        let mut offset = 0;
+        let aovs = passes
+            .iter()
+            .map(|aov| {
+                let channels = aov.channels().len();
+                let values = aov_values
+                    .iter()
+                    .flat_map(|pixel| &pixel[offset..offset + channels])
+                    .copied()
+                    .collect();
+                offset += channels;
+
+                Aov {
+                    name: aov.name().to_string(),
+                    channels: aov.channels().iter().map(|c| c.to_string()).collect(),
+                    values,
+                }
             })
             .collect();
 
-        shared::data::Frame {
+        let mut frame = Frame {
             pixels,
-            height: frame_height,
-            width: frame_width,
+            height: region.height,
+            width: region.width,
+            aovs,
+        };
+
+        if let Some(denoise) = self.film.denoise {
+            frame.pixels = denoise.apply(&frame);
+            frame.aovs.truncate(self.film.aovs.len());
+        }
+
+        if settings.crop_output == CropOutput::FullFrame
+            && !region.is_full(frame_width, frame_height)
+        {
+            frame = full_frame(frame, &region, &film, pass > 0);
+            if collect_stats {
+                let mut pixel_cost = vec![0.0; (frame_width * frame_height) as usize];
+                region.paste(&stats.pixel_cost, 1, &mut pixel_cost, frame_width);
+                stats.pixel_cost = pixel_cost;
+            }
         }
+
+        stats.render_time = start.elapsed();
+        let checkpoint = Checkpoint {
+            film,
+            passes: pass + 1,
+        };
+        (frame, stats, checkpoint)
+    }
+}
+
+impl Render for Renderer {
+    fn render(&self, settings: &RenderSettings) -> Result<Frame, RenderError> {
+        Ok(self.render_frame(settings, None, false)?.0)
     }
 
     fn render_pixel(
         &self,
         x: i32,
         y: i32,
-        frame_width: i32,
-        frame_height: i32,
-        samples_per_pixel: i16,
-    ) -> shared::data::Pixel {
-        let mut pixel_color = Color::default();
-        for _ in 0..samples_per_pixel {
-            let u = (x as f32 + rand_f32()) / (frame_width - 1) as f32;
-            let v = (y as f32 + rand_f32()) / (frame_height - 1) as f32;
-            let ray = self.scene.camera.get_ray(u, v);
-
-            pixel_color =
-                pixel_color + ray_color_material(&ray, &self.scene.world, MAX_RECURSION_DEPTH);
+        settings: &RenderSettings,
+    ) -> Result<Pixel, RenderError> {
+        settings.validate()?;
+        let (frame_width, frame_height) = (settings.width, settings.height);
+        if !(0..frame_width).contains(&x) || !(0..frame_height).contains(&y) {
+            return Err(RenderError::InvalidSettings(format!(
+                "pixel ({}, {}) outside the {}x{} frame",
+                x, y, frame_width, frame_height
+            )));
         }
 
-        shared::data::Pixel {
-            r: sampled_value_to_normalized(pixel_color.x(), samples_per_pixel.into()).into(),
-            g: sampled_value_to_normalized(pixel_color.y(), samples_per_pixel.into()).into(),
-            b: sampled_value_to_normalized(pixel_color.z(), samples_per_pixel.into()).into(),
-        }
-    }
-}
+        // Single pixel has no neighbours to splat to, gather samples from the filter footprint
+        let camera = self
+            .camera
+            .build(frame_width as f32 / frame_height as f32)?;
+        let radius = self.film.filter.radius();
+        let mut weighted_color = Color::default();
+        let mut weighted_alpha = 0.0;
+        let mut weight_sum = 0.0;
+        let mut stats = TraceStats::default();
+        self.reseed_pixel(x, y, settings);
+        for _ in 0..settings.samples_per_pixel {
+            let offset_x = (2.0 * rand_f32() - 1.0) * radius;
+            let offset_y = (2.0 * rand_f32() - 1.0) * radius;
+            let sample = self.sample(
+                &camera,
+                (x as f32 + 0.5 + offset_x) / (frame_width - 1) as f32,
+                (y as f32 + 0.5 + offset_y) / (frame_height - 1) as f32,
+                settings,
+                &mut stats,
+            );
 
-impl Default for Renderer {
-    fn default() -> Self {
-        Self {
-            scene: Default::default(),
+            let weight = self.film.filter.evaluate(-offset_x, -offset_y);
+            weighted_color = weighted_color + sample.color * weight;
+            weighted_alpha += sample.alpha() * weight;
+            weight_sum += weight;
         }
+
+        Ok(match weight_sum > 0.0 {
+            true => to_pixel(
+                &(weighted_color / weight_sum),
+                (weighted_alpha / weight_sum).clamp(0.0, 1.0),
+            ),
+            false => Pixel::default(),
+        })
     }
 }
 
-fn ray_color_material(ray: &Ray, world: &HitCollection, depth: i16) -> Color {
-    // Recursion protection
-    if depth <= 0 {
-        return Color::default();
-    }
+/**
+ * Frame of the full size with the rendered region pasted in. Other pixels show samples of
+ * earlier passes when resuming a checkpoint and are left empty otherwise.
+ */
+fn full_frame(region_frame: Frame, region: &Region, film: &Film, resumed: bool) -> Frame {
+    let (width, height) = (film.width(), film.height());
+    let mut pixels = match resumed {
+        true => film_pixels(film, &Region::full(width, height)),
+        false => vec![Pixel::default(); (width * height) as usize],
+    };
+    region.paste(&region_frame.pixels, 1, &mut pixels, width);
 
-    let hit_res = world.hit(ray, 0.001, f32::MAX);
-    match hit_res {
-        Some(hit) => {
-            let scatter_res = hit.material.scatter(ray, &hit);
-            match scatter_res {
-                Some(scatter) => {
-                    return scatter.attenuation
-                        * ray_color_material(&scatter.scattered_ray, world, depth - 1)
-                }
-                None => return Color::default(),
-            }
-        }
-        None => (),
+    let aovs = region_frame
+        .aovs
+        .into_iter()
+        .map(|aov| {
+            let channels = aov.channels.len();
+            let mut values = vec![0.0; (width * height) as usize * channels];
+            region.paste(&aov.values, channels, &mut values, width);
+            Aov { values, ..aov }
+        })
+        .collect();
+
+    Frame {
+        pixels,
+        height,
+        width,
+        aovs,
     }
+}
+
+fn film_pixels(film: &Film, region: &Region) -> Vec<Pixel> {
+    region
+        .rows()
+        .flat_map(|y| region.columns().map(move |x| (x, y)))
+        .map(|(x, y)| to_pixel(&film.pixel(x, y), film.alpha(x, y)))
+        .collect()
+}
 
-    let unit_direction = ray.unit_direction();
-    let t = 0.5 * (unit_direction.y() + 1.0);
-    Color::new([1.0, 1.0, 1.0]) * (1.0 - t) + Color::new([0.5, 0.7, 1.0]) * t
+fn to_pixel(color: &Color, alpha: f32) -> Pixel {
+    Pixel::new(color.x(), color.y(), color.z(), alpha)
 }
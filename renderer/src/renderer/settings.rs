@@ -0,0 +1,14 @@
+use super::{aov::AovType, denoise::DenoiseMethod, filter::FilterType};
+
+/**
+ * How samples become the output image: reconstruction, extra passes and denoising
+ */
+#[derive(Clone, Debug, Default)]
+pub struct FilmSettings {
+    /// Reconstruction filter used to splat samples to pixels
+    pub filter: FilterType,
+    /// Render passes to produce into Frame::aovs next to the beauty image
+    pub aovs: Vec<AovType>,
+    /// Filter applied to the beauty image after rendering
+    pub denoise: Option<DenoiseMethod>,
+}
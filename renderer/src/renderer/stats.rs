@@ -0,0 +1,288 @@
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use shared::data::{srgb_to_linear, Frame, Pixel};
+
+use crate::{
+    shapes::{
+        hit_collection::{intersection_tests, HitCollection},
+        hit_record::HitRecord,
+        traits::Hit,
+    },
+    util::ray::Ray,
+};
+
+/**
+ * Counters of the rays traced by one thread. Disabled counters skip the timing so
+ * tracing costs the same as without statistics.
+ */
+#[derive(Clone, Default)]
+pub struct TraceStats {
+    enabled: bool,
+    pub camera_rays: u64,
+    pub bounce_rays: u64,
+    /// Integrator doesn't sample lights directly yet, kept so reports stay comparable
+    pub shadow_rays: u64,
+    /// Objects tested against rays
+    pub intersection_tests: u64,
+    /// Thread time spent finding closest hits
+    pub intersection_time: Duration,
+    /// Thread time spent scattering rays at surfaces
+    pub shading_time: Duration,
+}
+
+impl TraceStats {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            ..Default::default()
+        }
+    }
+
+    /**
+     * Closest hit of ray in world, path segment number tells camera rays from bounces
+     */
+    pub fn intersect(
+        &mut self,
+        world: &HitCollection,
+        ray: &Ray,
+        bounces: i16,
+    ) -> Option<HitRecord> {
+        if !self.enabled {
+            return world.hit(ray, 0.001, f32::MAX);
+        }
+
+        let tests = intersection_tests();
+        let start = Instant::now();
+        let hit = world.hit(ray, 0.001, f32::MAX);
+        self.intersection_time += start.elapsed();
+        self.intersection_tests += intersection_tests() - tests;
+
+        match bounces {
+            0 => self.camera_rays += 1,
+            _ => self.bounce_rays += 1,
+        }
+        hit
+    }
+
+    pub fn shade<T>(&mut self, shading: impl FnOnce() -> T) -> T {
+        if !self.enabled {
+            return shading();
+        }
+
+        let start = Instant::now();
+        let result = shading();
+        self.shading_time += start.elapsed();
+        result
+    }
+
+    pub fn merge(&mut self, other: &TraceStats) {
+        self.camera_rays += other.camera_rays;
+        self.bounce_rays += other.bounce_rays;
+        self.shadow_rays += other.shadow_rays;
+        self.intersection_tests += other.intersection_tests;
+        self.intersection_time += other.intersection_time;
+        self.shading_time += other.shading_time;
+    }
+
+    fn rays(&self) -> u64 {
+        self.camera_rays + self.bounce_rays + self.shadow_rays
+    }
+}
+
+/**
+ * Statistics of one or more rendered frames
+ */
+#[derive(Clone, Default)]
+pub struct RenderStats {
+    pub frames: u32,
+    pub samples: u64,
+    /// Wall clock time including post-processing
+    pub render_time: Duration,
+    pub trace: TraceStats,
+    /// Thread seconds spent on each pixel in Frame::pixels order, frames appended after each other
+    pub pixel_cost: Vec<f32>,
+}
+
+impl RenderStats {
+    pub fn merge(&mut self, other: &RenderStats) {
+        self.frames += other.frames;
+        self.samples += other.samples;
+        self.render_time += other.render_time;
+        self.trace.merge(&other.trace);
+        self.pixel_cost.extend(&other.pixel_cost);
+    }
+
+    /**
+     * Summary with derived rates, serializable as JSON
+     */
+    pub fn report(&self) -> StatsReport {
+        let rays = self.trace.rays();
+        let per = |value: f64, count: u64| match count {
+            0 => 0.0,
+            count => value / count as f64,
+        };
+
+        let mut costs = self.pixel_cost.clone();
+        costs.sort_by(f32::total_cmp);
+        let thread_seconds: f64 = costs.iter().map(|&cost| cost as f64).sum();
+
+        StatsReport {
+            frames: self.frames,
+            pixels: costs.len() as u64,
+            samples: self.samples,
+            render_seconds: self.render_time.as_secs_f64(),
+            camera_rays: self.trace.camera_rays,
+            bounce_rays: self.trace.bounce_rays,
+            shadow_rays: self.trace.shadow_rays,
+            rays_per_second: rays as f64 / self.render_time.as_secs_f64().max(1e-9),
+            intersection_tests_per_ray: per(self.trace.intersection_tests as f64, rays),
+            average_path_length: per(
+                (self.trace.camera_rays + self.trace.bounce_rays) as f64,
+                self.trace.camera_rays,
+            ),
+            thread_seconds,
+            intersection_seconds: self.trace.intersection_time.as_secs_f64(),
+            shading_seconds: self.trace.shading_time.as_secs_f64(),
+            pixel_cost: CostSummary {
+                min: costs.first().copied().unwrap_or_default(),
+                median: percentile(&costs, 0.5),
+                p99: percentile(&costs, 0.99),
+                max: costs.last().copied().unwrap_or_default(),
+            },
+        }
+    }
+
+    /**
+     * False color image of the time spent on each pixel of a single frame. Colors are scaled
+     * to the 99th percentile so a few slow pixels don't flatten the rest of the map.
+     */
+    pub fn heatmap(&self, width: i32, height: i32) -> Frame {
+        let costs = &self.pixel_cost[..(width * height) as usize];
+        let mut sorted = costs.to_vec();
+        sorted.sort_by(f32::total_cmp);
+        let scale = percentile(&sorted, 0.99).max(f32::MIN_POSITIVE);
+
+        Frame {
+            pixels: costs
+                .iter()
+                .map(|cost| heat_color((cost / scale).min(1.0)))
+                .collect(),
+            height,
+            width,
+            aovs: vec![],
+        }
+    }
+}
+
+impl Display for RenderStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let report = self.report();
+        let share = |seconds: f64| 100.0 * seconds / report.thread_seconds.max(1e-9);
+
+        writeln!(f, "Render statistics")?;
+        writeln!(
+            f,
+            "  Render time:          {:.3} s, {} frame(s), {} samples",
+            report.render_seconds, report.frames, report.samples
+        )?;
+        writeln!(f, "  Camera rays:          {}", report.camera_rays)?;
+        writeln!(f, "  Bounce rays:          {}", report.bounce_rays)?;
+        writeln!(f, "  Shadow rays:          {}", report.shadow_rays)?;
+        writeln!(
+            f,
+            "  Rays per second:      {:.3} M",
+            report.rays_per_second / 1e6
+        )?;
+        writeln!(
+            f,
+            "  Intersection tests:   {:.2} per ray",
+            report.intersection_tests_per_ray
+        )?;
+        writeln!(
+            f,
+            "  Average path length:  {:.2} rays",
+            report.average_path_length
+        )?;
+        writeln!(
+            f,
+            "  Intersection time:    {:.3} s ({:.1} % of thread time)",
+            report.intersection_seconds,
+            share(report.intersection_seconds)
+        )?;
+        writeln!(
+            f,
+            "  Shading time:         {:.3} s ({:.1} % of thread time)",
+            report.shading_seconds,
+            share(report.shading_seconds)
+        )?;
+        write!(
+            f,
+            "  Pixel cost:           min {:.1} µs, median {:.1} µs, p99 {:.1} µs, max {:.1} µs",
+            report.pixel_cost.min * 1e6,
+            report.pixel_cost.median * 1e6,
+            report.pixel_cost.p99 * 1e6,
+            report.pixel_cost.max * 1e6
+        )
+    }
+}
+
+#[derive(Serialize)]
+pub struct StatsReport {
+    pub frames: u32,
+    pub pixels: u64,
+    pub samples: u64,
+    pub render_seconds: f64,
+    pub camera_rays: u64,
+    pub bounce_rays: u64,
+    pub shadow_rays: u64,
+    pub rays_per_second: f64,
+    pub intersection_tests_per_ray: f64,
+    /// Rays traced per camera ray, including the camera ray
+    pub average_path_length: f64,
+    /// Time all threads spent on pixels
+    pub thread_seconds: f64,
+    pub intersection_seconds: f64,
+    pub shading_seconds: f64,
+    /// Seconds per pixel
+    pub pixel_cost: CostSummary,
+}
+
+#[derive(Serialize)]
+pub struct CostSummary {
+    pub min: f32,
+    pub median: f32,
+    pub p99: f32,
+    pub max: f32,
+}
+
+fn percentile(sorted: &[f32], fraction: f32) -> f32 {
+    match sorted.len() {
+        0 => 0.0,
+        len => sorted[((len - 1) as f32 * fraction).round() as usize],
+    }
+}
+
+/**
+ * Black, blue, red, yellow, white ramp for value in [0, 1]
+ */
+fn heat_color(value: f32) -> Pixel {
+    const STOPS: [[f32; 3]; 5] = [
+        [0.0, 0.0, 0.0],
+        [0.1, 0.1, 0.8],
+        [0.9, 0.1, 0.1],
+        [1.0, 0.9, 0.1],
+        [1.0, 1.0, 1.0],
+    ];
+
+    let position = value * (STOPS.len() - 1) as f32;
+    let index = (position as usize).min(STOPS.len() - 2);
+    let t = position - index as f32;
+    // Stops are display values, frames hold linear color
+    let channel = |c: usize| srgb_to_linear((1.0 - t) * STOPS[index][c] + t * STOPS[index + 1][c]);
+
+    Pixel::opaque(channel(0), channel(1), channel(2))
+}
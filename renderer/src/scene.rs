@@ -1,2 +1,8 @@
+pub mod animation;
+pub mod builder;
 pub mod camera;
+pub mod camera_description;
+pub mod material_description;
 pub mod scene;
+pub mod scene_description;
+pub mod scene_file;
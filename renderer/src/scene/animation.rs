@@ -0,0 +1,86 @@
+pub mod track;
+
+use serde::Deserialize;
+
+use self::track::Track;
+
+use super::{camera_description::CameraDescription, material_description::MaterialDescription};
+
+/**
+ * Keyframed changes of a scene over time
+ */
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Timeline {
+    pub camera: CameraTracks,
+    pub objects: Vec<ObjectAnimation>,
+}
+
+impl Timeline {
+    /**
+     * Checks values that can't be interpolated into a valid scene
+     */
+    pub fn validate(&self) -> Result<(), String> {
+        for animation in &self.objects {
+            if let Some(key) = animation
+                .scale
+                .keys()
+                .iter()
+                .find(|key| !key.value.is_finite() || key.value <= 0.0)
+            {
+                return Err(format!(
+                    "scale of object {} must be positive, got {} at {} s",
+                    animation.object, key.value, key.time
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/**
+ * Camera parameters overridden by keyframes, empty tracks keep the camera's own value
+ */
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct CameraTracks {
+    pub look_from: Track<[f32; 3]>,
+    pub look_at: Track<[f32; 3]>,
+    pub up: Track<[f32; 3]>,
+    pub fov: Track<f32>,
+    pub aperture: Track<f32>,
+    pub focus_distance: Track<f32>,
+}
+
+impl CameraTracks {
+    pub fn apply(&self, camera: &CameraDescription, time: f32) -> CameraDescription {
+        let mut camera = camera.clone();
+        camera.look_from = self.look_from.sample(time).unwrap_or(camera.look_from);
+        camera.look_at = self.look_at.sample(time).unwrap_or(camera.look_at);
+        camera.up = self.up.sample(time).unwrap_or(camera.up);
+        camera.fov = self.fov.sample(time).unwrap_or(camera.fov);
+        camera.aperture = self.aperture.sample(time).unwrap_or(camera.aperture);
+        camera.focus_distance = self.focus_distance.sample(time).or(camera.focus_distance);
+        camera
+    }
+}
+
+/**
+ * Transform and material keyframes of a single object. Transform is applied around the pivot
+ * in order scale, rotation, translation.
+ */
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ObjectAnimation {
+    /// Index of object in scene
+    pub object: usize,
+    pub pivot: [f32; 3],
+    pub translation: Track<[f32; 3]>,
+    /// Euler angles in degrees, applied around x, then y, then z
+    pub rotation: Track<[f32; 3]>,
+    /// Uniform scale
+    pub scale: Track<f32>,
+    /// Replaces the object's material. Parameters of keys of the same kind are interpolated,
+    /// the kind switches halfway between keys of different kinds.
+    pub material: Track<MaterialDescription>,
+}
@@ -0,0 +1,160 @@
+use serde::Deserialize;
+
+/**
+ * How values change from a keyframe to the next one
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Interpolation {
+    #[default]
+    Linear,
+    /// Cubic Bézier with automatic handles, smooth through neighbouring keyframes and
+    /// easing in and out at the first and last keyframe
+    Bezier,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Keyframe<T> {
+    /// Seconds from start of the animation
+    pub time: f32,
+    pub value: T,
+    /// Interpolation towards the next keyframe
+    #[serde(default)]
+    pub interpolation: Interpolation,
+}
+
+/**
+ * Keyframes of a single animated value, sorted by time. Value is held constant before the
+ * first and after the last keyframe.
+ */
+#[derive(Clone, Debug, Deserialize)]
+#[serde(from = "Vec<Keyframe<T>>")]
+pub struct Track<T> {
+    keys: Vec<Keyframe<T>>,
+}
+
+impl<T> Default for Track<T> {
+    fn default() -> Self {
+        Self { keys: vec![] }
+    }
+}
+
+impl<T> From<Vec<Keyframe<T>>> for Track<T> {
+    fn from(mut keys: Vec<Keyframe<T>>) -> Self {
+        keys.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Self { keys }
+    }
+}
+
+/**
+ * Values which can be blended as weighted sums of keyframe values
+ */
+pub trait Interpolate: Clone {
+    fn weighted_sum(terms: &[(&Self, f32)]) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn weighted_sum(terms: &[(&Self, f32)]) -> Self {
+        terms.iter().map(|(value, weight)| *value * weight).sum()
+    }
+}
+
+impl Interpolate for [f32; 3] {
+    fn weighted_sum(terms: &[(&Self, f32)]) -> Self {
+        let mut sum = [0.0; 3];
+        for (value, weight) in terms {
+            for (sum, value) in sum.iter_mut().zip(value.iter()) {
+                *sum += value * weight;
+            }
+        }
+        sum
+    }
+}
+
+impl<T> Track<T> {
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn keys(&self) -> &[Keyframe<T>] {
+        &self.keys
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Track<U> {
+        Track {
+            keys: self
+                .keys
+                .iter()
+                .map(|key| Keyframe {
+                    time: key.time,
+                    value: f(&key.value),
+                    interpolation: key.interpolation,
+                })
+                .collect(),
+        }
+    }
+
+    /**
+     * Keyframes surrounding time and position between them in range [0, 1].
+     * None for empty tracks.
+     */
+    pub fn segment(&self, time: f32) -> Option<(usize, usize, f32)> {
+        let next = self.keys.partition_point(|key| key.time <= time);
+        match next {
+            0 if self.keys.is_empty() => None,
+            0 => Some((0, 0, 0.0)),
+            next if next == self.keys.len() => Some((next - 1, next - 1, 0.0)),
+            next => {
+                let (from, to) = (&self.keys[next - 1], &self.keys[next]);
+                let fraction = (time - from.time) / (to.time - from.time);
+                Some((next - 1, next, fraction))
+            }
+        }
+    }
+}
+
+impl<T: Interpolate> Track<T> {
+    /**
+     * Value at time, None for empty tracks
+     */
+    pub fn sample(&self, time: f32) -> Option<T> {
+        let (from, to, t) = self.segment(time)?;
+        if from == to {
+            return Some(self.keys[from].value.clone());
+        }
+
+        let (start, end) = (&self.keys[from], &self.keys[to]);
+        if start.interpolation == Interpolation::Linear {
+            return Some(T::weighted_sum(&[(&start.value, 1.0 - t), (&end.value, t)]));
+        }
+
+        // Bézier control points P1 = P0 + m0 dt / 3 and P2 = P3 - m1 dt / 3, slopes m are
+        // Catmull-Rom differences of neighbouring keys and zero at the ends of the track
+        let duration = end.time - start.time;
+        let weights = [
+            (1.0 - t).powi(3),
+            3.0 * (1.0 - t).powi(2) * t,
+            3.0 * (1.0 - t) * t * t,
+            t.powi(3),
+        ];
+
+        let mut terms = vec![
+            (&start.value, weights[0] + weights[1]),
+            (&end.value, weights[2] + weights[3]),
+        ];
+        let mut add_slope = |before: usize, after: usize, weight: f32| {
+            let (before, after) = (&self.keys[before], &self.keys[after]);
+            let scale = weight * duration / (3.0 * (after.time - before.time));
+            terms.push((&after.value, scale));
+            terms.push((&before.value, -scale));
+        };
+        if from > 0 {
+            add_slope(from - 1, to, weights[1]);
+        }
+        if to + 1 < self.keys.len() {
+            add_slope(from, to + 1, -weights[2]);
+        }
+
+        Some(T::weighted_sum(&terms))
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{
+    materials::material::MaterialType,
+    shapes::{hit_collection::HitCollection, sphere::Sphere, traits::Hit, triangle::Triangle},
+    util::point::Point,
+};
+
+use super::{camera_description::CameraDescription, scene::Scene};
+
+/**
+ * Assembles a scene from shapes and cameras. Objects get IDs in the order they are added.
+ */
+#[derive(Default)]
+pub struct SceneBuilder {
+    cameras: Vec<CameraDescription>,
+    world: HitCollection,
+}
+
+impl SceneBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * Adds a named camera, the first one added is rendered unless another is picked
+     */
+    pub fn with_camera(mut self, camera: CameraDescription) -> Self {
+        self.cameras.push(camera);
+        self
+    }
+
+    pub fn with_cameras(mut self, cameras: impl IntoIterator<Item = CameraDescription>) -> Self {
+        self.cameras.extend(cameras);
+        self
+    }
+
+    pub fn with_sphere(self, center: Point, radius: f32, material: MaterialType) -> Self {
+        self.with_object(Sphere::new_boxed(center, radius, material))
+    }
+
+    /**
+     * Triangle with vertices in counter clockwise order seen from the front, UVs of each vertex
+     */
+    pub fn with_triangle(
+        self,
+        vertices: [Point; 3],
+        uvs: [[f32; 2]; 3],
+        material: MaterialType,
+    ) -> Self {
+        self.with_object(Triangle::new_boxed(vertices, uvs, material))
+    }
+
+    /**
+     * Any shape, including ones defined outside this crate
+     */
+    pub fn with_object(mut self, object: Box<dyn Hit + Sync>) -> Self {
+        self.world.add(object);
+        self
+    }
+
+    /**
+     * Scene with the added objects, gets the default camera if none was added
+     */
+    pub fn build(self) -> Scene {
+        let cameras = match self.cameras.is_empty() {
+            true => vec![CameraDescription::default()],
+            false => self.cameras,
+        };
+
+        Scene {
+            cameras,
+            world: self.world,
+        }
+    }
+}
@@ -1,62 +1,79 @@
+pub mod aperture;
+pub mod equirectangular;
+pub mod fisheye;
+pub mod omni_stereo;
+pub mod orthographic;
+pub mod perspective;
+pub mod physical;
+pub mod stereo;
+
 use crate::{
-    math::vec3::Vec3,
+    math::{onb::Onb, vec3::Vec3},
     util::{point::Point, ray::Ray},
 };
 
-pub struct Camera {
-    origin: Point,
-    lower_left_corner: Point,
-    horizontal: Vec3,
-    vertical: Vec3,
-    u: Vec3,
-    v: Vec3,
-    lens_radius: f32,
+use self::{
+    equirectangular::Equirectangular, fisheye::Fisheye, omni_stereo::OmniStereo,
+    orthographic::Orthographic, perspective::Perspective, physical::PhysicalCamera,
+    stereo::StereoPerspective,
+};
+
+/**
+ * Projection from image coordinates to camera rays
+ */
+pub trait CameraModel {
+    /**
+     * Ray through image position (u, v), both in range [0, 1] with v = 0 at the bottom.
+     * None when the position is outside of the projected area, for example corners of a circular fisheye.
+     */
+    fn get_ray(&self, u: f32, v: f32) -> Option<Ray>;
+
+    /**
+     * Multiplier applied to radiance arriving through the camera
+     */
+    fn exposure(&self) -> f32 {
+        1.0
+    }
+}
+
+pub enum CameraType {
+    Perspective(Perspective),
+    Orthographic(Orthographic),
+    Fisheye(Fisheye),
+    Equirectangular(Equirectangular),
+    StereoPerspective(StereoPerspective),
+    OmniStereo(OmniStereo),
+    Physical(PhysicalCamera),
 }
 
-impl Camera {
-    pub fn new(
-        look_from: Point,
-        look_to: Point,
-        view_up: Vec3,
-        vfov_rad: f32,
-        aspect_ratio: f32,
-        aperture: f32,
-        focus_dist: f32,
-    ) -> Self {
-        let h = (vfov_rad / 2.0).tan();
-
-        let viewport_height = 2.0 * h;
-        let viewport_width = aspect_ratio * viewport_height;
-
-        let w = Vec3::unit_vector(&(look_from - look_to));
-        let u = Vec3::unit_vector(&view_up.cross(&w));
-        let v = w.cross(&u);
-
-        let origin = look_from;
-        let horizontal = u * viewport_width * focus_dist;
-        let vertical = v * viewport_height * focus_dist;
-        let lower_left_corner = origin - horizontal / 2.0 - vertical / 2.0 - w * focus_dist;
-
-        let lens_radius = aperture / 2.0;
-
-        Self {
-            origin,
-            lower_left_corner,
-            horizontal,
-            vertical,
-            u,
-            v,
-            lens_radius,
+impl CameraModel for CameraType {
+    fn get_ray(&self, u: f32, v: f32) -> Option<Ray> {
+        match self {
+            CameraType::Perspective(camera) => camera.get_ray(u, v),
+            CameraType::Orthographic(camera) => camera.get_ray(u, v),
+            CameraType::Fisheye(camera) => camera.get_ray(u, v),
+            CameraType::Equirectangular(camera) => camera.get_ray(u, v),
+            CameraType::StereoPerspective(camera) => camera.get_ray(u, v),
+            CameraType::OmniStereo(camera) => camera.get_ray(u, v),
+            CameraType::Physical(camera) => camera.get_ray(u, v),
         }
     }
 
-    pub fn get_ray(&self, u: f32, v: f32) -> Ray {
-        let rd = Vec3::random_in_unit_disk() * self.lens_radius;
-        let offset = self.u * rd.x() + self.v * rd.y();
-
-        Ray::new(
-            self.origin + offset,
-            self.lower_left_corner + self.horizontal * u + self.vertical * v - self.origin - offset,
-        )
+    fn exposure(&self) -> f32 {
+        match self {
+            CameraType::Physical(camera) => camera.exposure(),
+            _ => 1.0,
+        }
     }
 }
+
+/**
+ * Camera basis: u points right, v up and w backwards from the view direction
+ */
+pub fn look_at_basis(look_from: &Point, look_to: &Point, view_up: &Vec3) -> Onb {
+    let w = Vec3::unit_vector(&(look_from - look_to));
+    let u = Vec3::unit_vector(&view_up.cross(&w));
+    let v = w.cross(&u);
+
+    Onb { u, v, w }
+}
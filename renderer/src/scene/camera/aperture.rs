@@ -0,0 +1,62 @@
+use std::f32::consts::PI;
+
+use crate::{
+    math::{random::rand_f32, vec3::Vec3},
+    textures::texture::{Texture, TextureType},
+};
+
+/**
+ * Rejection sampling attempts for mask apertures before falling back to the lens center
+ */
+const MASK_ATTEMPTS: usize = 64;
+
+/**
+ * Shape of the lens opening, which is also the shape of out of focus highlights (bokeh).
+ * Shapes fit inside the unit disk.
+ */
+#[derive(Clone)]
+pub enum ApertureShape {
+    Circle,
+    /// Regular polygon formed by aperture blades, rotation in radians
+    Polygon {
+        blades: u32,
+        rotation: f32,
+    },
+    /// Image over [-1, 1] x [-1, 1], first channel is the transmission in range [0, 1]
+    Mask(TextureType),
+}
+
+impl ApertureShape {
+    /**
+     * Uniformly distributed point on the aperture, z is zero
+     */
+    pub fn sample(&self) -> Vec3 {
+        match self {
+            ApertureShape::Circle => Vec3::random_in_unit_disk(),
+            ApertureShape::Polygon { blades, rotation } => {
+                // Equal area triangles between center and neighbouring corners
+                let blades = (*blades).max(3);
+                let step = 2.0 * PI / blades as f32;
+                let angle = rotation + step * (rand_f32() * blades as f32).floor();
+                let corner_a = Vec3::new([angle.cos(), angle.sin(), 0.0]);
+                let corner_b = Vec3::new([(angle + step).cos(), (angle + step).sin(), 0.0]);
+
+                let (mut a, mut b) = (rand_f32(), rand_f32());
+                if a + b > 1.0 {
+                    (a, b) = (1.0 - a, 1.0 - b);
+                }
+                corner_a * a + corner_b * b
+            }
+            ApertureShape::Mask(mask) => {
+                let origin = Vec3::default();
+                for _ in 0..MASK_ATTEMPTS {
+                    let (u, v) = (rand_f32(), rand_f32());
+                    if rand_f32() < mask.scalar(u, v, &origin) {
+                        return Vec3::new([2.0 * u - 1.0, 2.0 * v - 1.0, 0.0]);
+                    }
+                }
+                origin
+            }
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use std::f32::consts::PI;
+
+use crate::{
+    math::{onb::Onb, vec3::Vec3},
+    util::{point::Point, ray::Ray},
+};
+
+use super::{look_at_basis, CameraModel};
+
+/**
+ * 360° panorama, u maps to longitude and v to latitude. View direction is at image center.
+ * Use 2:1 aspect ratio for undistorted output.
+ */
+pub struct Equirectangular {
+    origin: Point,
+    basis: Onb,
+}
+
+impl Equirectangular {
+    pub fn new(look_from: Point, look_to: Point, view_up: Vec3) -> Self {
+        Self {
+            origin: look_from,
+            basis: look_at_basis(&look_from, &look_to, &view_up),
+        }
+    }
+}
+
+impl CameraModel for Equirectangular {
+    fn get_ray(&self, u: f32, v: f32) -> Option<Ray> {
+        let longitude = (u - 0.5) * 2.0 * PI;
+        let latitude = (v - 0.5) * PI;
+        let local = Vec3::new([
+            latitude.cos() * longitude.sin(),
+            latitude.sin(),
+            -latitude.cos() * longitude.cos(),
+        ]);
+
+        Some(Ray::new(self.origin, self.basis.to_world(&local)))
+    }
+}
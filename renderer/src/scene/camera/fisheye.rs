@@ -0,0 +1,90 @@
+use serde::Deserialize;
+
+use crate::{
+    math::{onb::Onb, vec3::Vec3},
+    util::{point::Point, ray::Ray},
+};
+
+use super::{look_at_basis, CameraModel};
+
+/**
+ * Mapping from angle between ray and view direction to distance from image center
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FisheyeProjection {
+    /// Distance grows linearly with angle
+    Equidistant,
+    /// Preserves area, distance grows with sin(angle / 2)
+    Equisolid,
+}
+
+/**
+ * Circular fisheye. Image circle touches top and bottom of the image, positions outside of it
+ * produce no ray.
+ */
+pub struct Fisheye {
+    origin: Point,
+    basis: Onb,
+    /// Angle between view direction and edge of image circle
+    max_angle: f32,
+    aspect_ratio: f32,
+    projection: FisheyeProjection,
+}
+
+impl Fisheye {
+    /**
+     * Field of view is measured across the image circle, 180° covers a hemisphere
+     */
+    pub fn new(
+        look_from: Point,
+        look_to: Point,
+        view_up: Vec3,
+        fov_rad: f32,
+        aspect_ratio: f32,
+        projection: FisheyeProjection,
+    ) -> Self {
+        Self {
+            origin: look_from,
+            basis: look_at_basis(&look_from, &look_to, &view_up),
+            max_angle: fov_rad / 2.0,
+            aspect_ratio,
+            projection,
+        }
+    }
+
+    /**
+     * Angle from view direction at normalized distance from image center
+     */
+    fn angle(&self, radius: f32) -> f32 {
+        match self.projection {
+            FisheyeProjection::Equidistant => radius * self.max_angle,
+            FisheyeProjection::Equisolid => {
+                2.0 * (radius * (self.max_angle / 2.0).sin())
+                    .clamp(-1.0, 1.0)
+                    .asin()
+            }
+        }
+    }
+}
+
+impl CameraModel for Fisheye {
+    fn get_ray(&self, u: f32, v: f32) -> Option<Ray> {
+        let x = (2.0 * u - 1.0) * self.aspect_ratio;
+        let y = 2.0 * v - 1.0;
+        let radius = (x * x + y * y).sqrt();
+        if radius > 1.0 {
+            return None;
+        }
+
+        let theta = self.angle(radius);
+        let phi = y.atan2(x);
+        let local = Vec3::new([
+            theta.sin() * phi.cos(),
+            theta.sin() * phi.sin(),
+            -theta.cos(),
+        ]);
+
+        Some(Ray::new(self.origin, self.basis.to_world(&local)))
+    }
+}
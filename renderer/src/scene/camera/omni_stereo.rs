@@ -0,0 +1,57 @@
+use std::f32::consts::PI;
+
+use crate::{
+    math::{onb::Onb, vec3::Vec3},
+    util::{point::Point, ray::Ray},
+};
+
+use super::{look_at_basis, stereo::StereoSettings, CameraModel};
+
+/**
+ * Omni-directional stereo (ODS) panorama. Each eye is an equirectangular image whose rays start
+ * on a circle of diameter IPD, tangent to the viewing direction, so stereo is correct for
+ * every horizontal view direction.
+ */
+pub struct OmniStereo {
+    origin: Point,
+    basis: Onb,
+    stereo: StereoSettings,
+}
+
+impl OmniStereo {
+    pub fn new(look_from: Point, look_to: Point, view_up: Vec3, stereo: StereoSettings) -> Self {
+        Self {
+            origin: look_from,
+            basis: look_at_basis(&look_from, &look_to, &view_up),
+            stereo,
+        }
+    }
+}
+
+impl CameraModel for OmniStereo {
+    fn get_ray(&self, u: f32, v: f32) -> Option<Ray> {
+        let (eye, u, v) = self.stereo.layout.split(u, v);
+        let longitude = (u - 0.5) * 2.0 * PI;
+        let latitude = (v - 0.5) * PI;
+
+        let direction = Vec3::new([
+            latitude.cos() * longitude.sin(),
+            latitude.sin(),
+            -latitude.cos() * longitude.cos(),
+        ]);
+        // Right vector of the horizontal view direction
+        let tangent = Vec3::new([longitude.cos(), 0.0, longitude.sin()]);
+        let offset = tangent * (eye.sign() * self.stereo.ipd / 2.0);
+
+        // Eyes at infinite convergence look parallel, otherwise towards the converged point
+        let direction = match self.stereo.convergence.is_finite() {
+            true => direction * self.stereo.convergence - offset,
+            false => direction,
+        };
+
+        Some(Ray::new(
+            self.origin + self.basis.to_world(&offset),
+            self.basis.to_world(&direction),
+        ))
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{
+    math::vec3::Vec3,
+    util::{point::Point, ray::Ray},
+};
+
+use super::{look_at_basis, CameraModel};
+
+/**
+ * Parallel projection, all rays share the view direction. Object size does not change with distance.
+ */
+pub struct Orthographic {
+    lower_left_corner: Point,
+    horizontal: Vec3,
+    vertical: Vec3,
+    direction: Vec3,
+}
+
+impl Orthographic {
+    /**
+     * View height is the world space height of the visible area
+     */
+    pub fn new(
+        look_from: Point,
+        look_to: Point,
+        view_up: Vec3,
+        view_height: f32,
+        aspect_ratio: f32,
+    ) -> Self {
+        let basis = look_at_basis(&look_from, &look_to, &view_up);
+
+        let horizontal = basis.u * view_height * aspect_ratio;
+        let vertical = basis.v * view_height;
+        let lower_left_corner = look_from - horizontal / 2.0 - vertical / 2.0;
+
+        Self {
+            lower_left_corner,
+            horizontal,
+            vertical,
+            direction: -basis.w,
+        }
+    }
+}
+
+impl CameraModel for Orthographic {
+    fn get_ray(&self, u: f32, v: f32) -> Option<Ray> {
+        Some(Ray::new(
+            self.lower_left_corner + self.horizontal * u + self.vertical * v,
+            self.direction,
+        ))
+    }
+}
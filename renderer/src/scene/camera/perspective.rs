@@ -0,0 +1,91 @@
+use crate::{
+    math::vec3::Vec3,
+    util::{point::Point, ray::Ray},
+};
+
+use super::{look_at_basis, CameraModel};
+
+/**
+ * Thin lens perspective camera with defocus blur
+ */
+#[derive(Clone)]
+pub struct Perspective {
+    origin: Point,
+    lower_left_corner: Point,
+    horizontal: Vec3,
+    vertical: Vec3,
+    u: Vec3,
+    v: Vec3,
+    lens_radius: f32,
+}
+
+impl Perspective {
+    pub fn new(
+        look_from: Point,
+        look_to: Point,
+        view_up: Vec3,
+        vfov_rad: f32,
+        aspect_ratio: f32,
+        aperture: f32,
+        focus_dist: f32,
+    ) -> Self {
+        let h = (vfov_rad / 2.0).tan();
+
+        let viewport_height = 2.0 * h;
+        let viewport_width = aspect_ratio * viewport_height;
+
+        let basis = look_at_basis(&look_from, &look_to, &view_up);
+
+        let origin = look_from;
+        let horizontal = basis.u * viewport_width * focus_dist;
+        let vertical = basis.v * viewport_height * focus_dist;
+        let lower_left_corner = origin - horizontal / 2.0 - vertical / 2.0 - basis.w * focus_dist;
+
+        let lens_radius = aperture / 2.0;
+
+        Self {
+            origin,
+            lower_left_corner,
+            horizontal,
+            vertical,
+            u: basis.u,
+            v: basis.v,
+            lens_radius,
+        }
+    }
+
+    /**
+     * Moves camera sideways keeping the image plane at focus distance in place, so both
+     * eyes of an off-axis stereo pair see the same window at that distance
+     */
+    pub fn with_eye_offset(self, offset: f32) -> Self {
+        Self {
+            origin: self.origin + self.u * offset,
+            ..self
+        }
+    }
+
+    /**
+     * Moves camera and its image plane sideways, eyes of a stereo pair moved this way look
+     * parallel and converge at infinity
+     */
+    pub fn with_parallel_eye_offset(self, offset: f32) -> Self {
+        Self {
+            origin: self.origin + self.u * offset,
+            lower_left_corner: self.lower_left_corner + self.u * offset,
+            ..self
+        }
+    }
+}
+
+impl CameraModel for Perspective {
+    fn get_ray(&self, u: f32, v: f32) -> Option<Ray> {
+        let rd = Vec3::random_in_unit_disk() * self.lens_radius;
+        let offset = self.u * rd.x() + self.v * rd.y();
+
+        Some(Ray::new(
+            self.origin + offset,
+            self.lower_left_corner + self.horizontal * u + self.vertical * v - self.origin - offset,
+        ))
+    }
+}
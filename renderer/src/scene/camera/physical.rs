@@ -0,0 +1,143 @@
+use crate::{
+    math::vec3::Vec3,
+    util::{point::Point, ray::Ray},
+};
+
+use super::{aperture::ApertureShape, look_at_basis, CameraModel};
+
+/**
+ * Exposure value at ISO 100 where scene radiance 1.0 maps to white, for example f/2.8 at 1/125 s
+ */
+const REFERENCE_EV100: f32 = 10.0;
+
+/**
+ * Photographic camera parameters. Lengths on the camera are in millimeters, scene units are meters.
+ */
+#[derive(Clone)]
+pub struct PhysicalSettings {
+    pub focal_length_mm: f32,
+    /// Sensor width and height, image is cropped to fit inside the sensor
+    pub sensor_mm: (f32, f32),
+    pub f_stop: f32,
+    /// Exposure time in seconds
+    pub shutter_s: f32,
+    pub iso: f32,
+    /// Distance to the plane in focus, in scene units
+    pub focus_distance: f32,
+    pub aperture: ApertureShape,
+    /// Optical vignetting strength, 0 disables. Lens barrel clips the aperture towards image
+    /// edges which gives cat-eye shaped bokeh and darker corners.
+    pub cat_eye: f32,
+}
+
+impl Default for PhysicalSettings {
+    fn default() -> Self {
+        Self {
+            focal_length_mm: 50.0,
+            sensor_mm: (36.0, 24.0),
+            f_stop: 2.8,
+            shutter_s: 1.0 / 125.0,
+            iso: 100.0,
+            focus_distance: 10.0,
+            aperture: ApertureShape::Circle,
+            cat_eye: 0.0,
+        }
+    }
+}
+
+impl PhysicalSettings {
+    /**
+     * Exposure value normalized to ISO 100
+     */
+    pub fn ev100(&self) -> f32 {
+        (self.f_stop * self.f_stop / self.shutter_s * 100.0 / self.iso).log2()
+    }
+
+    /**
+     * Aperture diameter in scene units
+     */
+    pub fn aperture_diameter(&self) -> f32 {
+        self.focal_length_mm / self.f_stop / 1000.0
+    }
+}
+
+/**
+ * Thin lens camera driven by focal length, sensor size, f-stop, shutter speed and ISO
+ */
+pub struct PhysicalCamera {
+    origin: Point,
+    lower_left_corner: Point,
+    horizontal: Vec3,
+    vertical: Vec3,
+    u: Vec3,
+    v: Vec3,
+    lens_radius: f32,
+    aperture: ApertureShape,
+    cat_eye: f32,
+    exposure: f32,
+}
+
+impl PhysicalCamera {
+    pub fn new(
+        look_from: Point,
+        look_to: Point,
+        view_up: Vec3,
+        aspect_ratio: f32,
+        settings: PhysicalSettings,
+    ) -> Self {
+        // Largest area of image aspect ratio inside the sensor
+        let (sensor_width, sensor_height) = settings.sensor_mm;
+        let (film_width, film_height) = match aspect_ratio > sensor_width / sensor_height {
+            true => (sensor_width, sensor_width / aspect_ratio),
+            false => (sensor_height * aspect_ratio, sensor_height),
+        };
+
+        // Similar triangles: film at focal length maps to viewport at focus distance
+        let scale = settings.focus_distance / settings.focal_length_mm;
+        let basis = look_at_basis(&look_from, &look_to, &view_up);
+        let horizontal = basis.u * film_width * scale;
+        let vertical = basis.v * film_height * scale;
+        let lower_left_corner =
+            look_from - horizontal / 2.0 - vertical / 2.0 - basis.w * settings.focus_distance;
+
+        Self {
+            origin: look_from,
+            lower_left_corner,
+            horizontal,
+            vertical,
+            u: basis.u,
+            v: basis.v,
+            lens_radius: settings.aperture_diameter() / 2.0,
+            exposure: 2.0_f32.powf(REFERENCE_EV100 - settings.ev100()),
+            aperture: settings.aperture,
+            cat_eye: settings.cat_eye,
+        }
+    }
+}
+
+impl CameraModel for PhysicalCamera {
+    fn get_ray(&self, u: f32, v: f32) -> Option<Ray> {
+        let lens = self.aperture.sample();
+
+        // Barrel opening is the aperture shifted away from the image center, samples
+        // outside of it are blocked
+        if self.cat_eye > 0.0 {
+            let image_offset = Vec3::new([2.0 * u - 1.0, 2.0 * v - 1.0, 0.0]);
+            if (lens - image_offset * self.cat_eye).length_squared() > 1.0 {
+                return None;
+            }
+        }
+
+        let rd = lens * self.lens_radius;
+        let offset = self.u * rd.x() + self.v * rd.y();
+
+        Some(Ray::new(
+            self.origin + offset,
+            self.lower_left_corner + self.horizontal * u + self.vertical * v - self.origin - offset,
+        ))
+    }
+
+    fn exposure(&self) -> f32 {
+        self.exposure
+    }
+}
@@ -0,0 +1,135 @@
+use serde::Deserialize;
+
+use crate::{
+    math::vec3::Vec3,
+    util::{point::Point, ray::Ray},
+};
+
+use super::{perspective::Perspective, CameraModel};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Eye {
+    Left,
+    Right,
+}
+
+impl Eye {
+    /**
+     * Direction of eye offset along camera right vector
+     */
+    pub fn sign(&self) -> f32 {
+        match self {
+            Eye::Left => -1.0,
+            Eye::Right => 1.0,
+        }
+    }
+}
+
+/**
+ * Placement of both eyes in a single frame
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StereoLayout {
+    /// Left eye on the left half
+    SideBySide,
+    /// Left eye on the top half
+    TopBottom,
+}
+
+impl StereoLayout {
+    /**
+     * Eye for image position and position inside that eye's view, both in range [0, 1]
+     */
+    pub fn split(&self, u: f32, v: f32) -> (Eye, f32, f32) {
+        match self {
+            StereoLayout::SideBySide if u < 0.5 => (Eye::Left, 2.0 * u, v),
+            StereoLayout::SideBySide => (Eye::Right, 2.0 * u - 1.0, v),
+            StereoLayout::TopBottom if v >= 0.5 => (Eye::Left, u, 2.0 * v - 1.0),
+            StereoLayout::TopBottom => (Eye::Right, u, 2.0 * v),
+        }
+    }
+
+    /**
+     * Aspect ratio of a single eye's view in image of given aspect ratio
+     */
+    pub fn eye_aspect_ratio(&self, aspect_ratio: f32) -> f32 {
+        match self {
+            StereoLayout::SideBySide => aspect_ratio / 2.0,
+            StereoLayout::TopBottom => aspect_ratio * 2.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct StereoSettings {
+    /// Interpupillary distance in scene units
+    pub ipd: f32,
+    /// Distance where both eyes see the same point, objects there appear at screen depth.
+    /// Infinity makes the eyes look parallel.
+    pub convergence: f32,
+    pub layout: StereoLayout,
+}
+
+/**
+ * Pair of off-axis perspective cameras. Eyes stay parallel and their image windows are shifted
+ * to meet at convergence distance, which avoids the vertical parallax of toed-in cameras.
+ * At infinite convergence the windows aren't shifted.
+ */
+pub struct StereoPerspective {
+    left: Perspective,
+    right: Perspective,
+    layout: StereoLayout,
+}
+
+impl StereoPerspective {
+    /**
+     * Aspect ratio is for a single eye, see StereoLayout::eye_aspect_ratio
+     */
+    pub fn new(
+        look_from: Point,
+        look_to: Point,
+        view_up: Vec3,
+        vfov_rad: f32,
+        aspect_ratio: f32,
+        stereo: StereoSettings,
+    ) -> Self {
+        let converging = stereo.convergence.is_finite();
+        // Window distance only matters for converging eyes
+        let window_distance = match converging {
+            true => stereo.convergence,
+            false => 1.0,
+        };
+        let center = Perspective::new(
+            look_from,
+            look_to,
+            view_up,
+            vfov_rad,
+            aspect_ratio,
+            0.0,
+            window_distance,
+        );
+        let eye = |eye: Eye| {
+            let offset = eye.sign() * stereo.ipd / 2.0;
+            match converging {
+                true => center.clone().with_eye_offset(offset),
+                false => center.clone().with_parallel_eye_offset(offset),
+            }
+        };
+
+        Self {
+            left: eye(Eye::Left),
+            right: eye(Eye::Right),
+            layout: stereo.layout,
+        }
+    }
+}
+
+impl CameraModel for StereoPerspective {
+    fn get_ray(&self, u: f32, v: f32) -> Option<Ray> {
+        match self.layout.split(u, v) {
+            (Eye::Left, u, v) => self.left.get_ray(u, v),
+            (Eye::Right, u, v) => self.right.get_ray(u, v),
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use serde::Deserialize;
+use shared::error::RenderError;
+
+use crate::{
+    math::vec3::Vec3,
+    textures::{image::ImageTexture, texture::TextureType},
+    util::point::Point,
+};
+
+use super::camera::{
+    aperture::ApertureShape,
+    equirectangular::Equirectangular,
+    fisheye::{Fisheye, FisheyeProjection},
+    omni_stereo::OmniStereo,
+    orthographic::Orthographic,
+    perspective::Perspective,
+    physical::{PhysicalCamera, PhysicalSettings},
+    stereo::{StereoLayout, StereoPerspective, StereoSettings},
+    CameraType,
+};
+
+/**
+ * Camera kind and its projection specific parameters
+ */
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Projection {
+    Perspective,
+    /// View height is the world space height of the visible area
+    Orthographic {
+        view_height: f32,
+    },
+    /// Field of view is measured across the image circle
+    Fisheye {
+        mapping: FisheyeProjection,
+    },
+    Equirectangular,
+    /// Convergence defaults to infinity, eyes looking parallel
+    Stereo {
+        ipd: f32,
+        layout: StereoLayout,
+        convergence: Option<f32>,
+    },
+    /// Convergence defaults to infinity, eyes looking parallel
+    OmniStereo {
+        ipd: f32,
+        layout: StereoLayout,
+        convergence: Option<f32>,
+    },
+    /// Photographic camera, field of view comes from focal length and sensor size and depth
+    /// of field from the f-stop, so fov and aperture of the description are not used
+    Physical {
+        focal_length_mm: f32,
+        /// Sensor width and height
+        sensor_mm: [f32; 2],
+        f_stop: f32,
+        /// Exposure time in seconds
+        shutter_s: f32,
+        iso: f32,
+        #[serde(default)]
+        aperture: ApertureDescription,
+        /// Optical vignetting strength, 0 disables
+        #[serde(default)]
+        cat_eye: f32,
+    },
+}
+
+impl Projection {
+    /**
+     * Physical camera with a 50 mm lens on a full frame sensor at f/2.8, 1/125 s and ISO 100
+     */
+    pub fn physical() -> Self {
+        let settings = PhysicalSettings::default();
+        Projection::Physical {
+            focal_length_mm: settings.focal_length_mm,
+            sensor_mm: [settings.sensor_mm.0, settings.sensor_mm.1],
+            f_stop: settings.f_stop,
+            shutter_s: settings.shutter_s,
+            iso: settings.iso,
+            aperture: ApertureDescription::Circle,
+            cat_eye: settings.cat_eye,
+        }
+    }
+}
+
+/**
+ * Lens opening of a physical camera as found in scene files
+ */
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ApertureDescription {
+    #[default]
+    Circle,
+    /// Rotation in degrees
+    Polygon {
+        blades: u32,
+        #[serde(default)]
+        rotation: f32,
+    },
+    /// PPM image, brightness is the transmission
+    Mask { path: String },
+}
+
+impl ApertureDescription {
+    pub fn build(&self) -> Result<ApertureShape, RenderError> {
+        match self {
+            ApertureDescription::Circle => Ok(ApertureShape::Circle),
+            ApertureDescription::Polygon { blades, rotation } => Ok(ApertureShape::Polygon {
+                blades: *blades,
+                rotation: rotation.to_radians(),
+            }),
+            ApertureDescription::Mask { path } => ImageTexture::from_ppm(path)
+                .map(|image| ApertureShape::Mask(TextureType::Image(image)))
+                .map_err(|err| {
+                    RenderError::Io(format!("could not read aperture mask {}: {}", path, err))
+                }),
+        }
+    }
+}
+
+/**
+ * Named camera placement as found in scene files. Aspect ratio is not part of the description,
+ * it comes from the rendered image size.
+ */
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct CameraDescription {
+    pub name: String,
+    pub look_from: [f32; 3],
+    pub look_at: [f32; 3],
+    pub up: [f32; 3],
+    /// Vertical field of view in degrees
+    pub fov: f32,
+    /// Lens diameter, 0 keeps everything in focus
+    pub aperture: f32,
+    /// Distance to plane in focus, defaults to distance between look_from and look_at
+    pub focus_distance: Option<f32>,
+    pub projection: Projection,
+}
+
+impl Default for CameraDescription {
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+            look_from: [3.0, 3.0, 2.0],
+            look_at: [0.0, 0.0, -1.0],
+            up: [0.0, 1.0, 0.0],
+            fov: 20.0,
+            aperture: 2.0,
+            focus_distance: None,
+            projection: Projection::Perspective,
+        }
+    }
+}
+
+impl CameraDescription {
+    /**
+     * Camera with given name, first camera when name is None
+     */
+    pub fn find<'a>(
+        cameras: &'a [CameraDescription],
+        name: Option<&str>,
+    ) -> Result<&'a CameraDescription, String> {
+        if cameras.is_empty() {
+            return Err("scene file defines no cameras".to_string());
+        }
+        let camera = match name {
+            Some(name) => cameras.iter().find(|camera| camera.name == name),
+            None => cameras.first(),
+        };
+
+        camera.ok_or_else(|| {
+            let names: Vec<&str> = cameras.iter().map(|camera| camera.name.as_str()).collect();
+            format!(
+                "unknown camera '{}', scene has: {}",
+                name.unwrap_or_default(),
+                names.join(", ")
+            )
+        })
+    }
+
+    pub fn focus_distance(&self) -> f32 {
+        self.focus_distance
+            .unwrap_or_else(|| (Point::new(self.look_from) - Point::new(self.look_at)).length())
+    }
+
+    /**
+     * Checks ranges of physical camera settings, exposure needs all of them positive
+     */
+    pub fn validate(&self) -> Result<(), RenderError> {
+        let Projection::Physical {
+            focal_length_mm,
+            sensor_mm,
+            f_stop,
+            shutter_s,
+            iso,
+            ..
+        } = self.projection
+        else {
+            return Ok(());
+        };
+
+        let settings = [
+            ("focal length", focal_length_mm),
+            ("sensor width", sensor_mm[0]),
+            ("sensor height", sensor_mm[1]),
+            ("f-stop", f_stop),
+            ("shutter time", shutter_s),
+            ("ISO", iso),
+        ];
+        for (name, value) in settings {
+            if !value.is_finite() || value <= 0.0 {
+                return Err(RenderError::InvalidSettings(format!(
+                    "{} must be positive, got {}",
+                    name, value
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * Fails when physical camera settings are out of range or an aperture mask can't be read
+     */
+    pub fn build(&self, aspect_ratio: f32) -> Result<CameraType, RenderError> {
+        self.validate()?;
+        let look_from = Point::new(self.look_from);
+        let look_at = Point::new(self.look_at);
+        let up = Vec3::new(self.up);
+        let fov = self.fov.to_radians();
+
+        let camera = match self.projection {
+            Projection::Perspective => CameraType::Perspective(Perspective::new(
+                look_from,
+                look_at,
+                up,
+                fov,
+                aspect_ratio,
+                self.aperture,
+                self.focus_distance(),
+            )),
+            Projection::Orthographic { view_height } => CameraType::Orthographic(
+                Orthographic::new(look_from, look_at, up, view_height, aspect_ratio),
+            ),
+            Projection::Fisheye { mapping } => CameraType::Fisheye(Fisheye::new(
+                look_from,
+                look_at,
+                up,
+                fov,
+                aspect_ratio,
+                mapping,
+            )),
+            Projection::Equirectangular => {
+                CameraType::Equirectangular(Equirectangular::new(look_from, look_at, up))
+            }
+            Projection::Stereo {
+                ipd,
+                layout,
+                convergence,
+            } => CameraType::StereoPerspective(StereoPerspective::new(
+                look_from,
+                look_at,
+                up,
+                fov,
+                layout.eye_aspect_ratio(aspect_ratio),
+                StereoSettings {
+                    ipd,
+                    convergence: convergence.unwrap_or(f32::INFINITY),
+                    layout,
+                },
+            )),
+            Projection::OmniStereo {
+                ipd,
+                layout,
+                convergence,
+            } => CameraType::OmniStereo(OmniStereo::new(
+                look_from,
+                look_at,
+                up,
+                StereoSettings {
+                    ipd,
+                    convergence: convergence.unwrap_or(f32::INFINITY),
+                    layout,
+                },
+            )),
+            Projection::Physical {
+                focal_length_mm,
+                sensor_mm,
+                f_stop,
+                shutter_s,
+                iso,
+                ref aperture,
+                cat_eye,
+            } => CameraType::Physical(PhysicalCamera::new(
+                look_from,
+                look_at,
+                up,
+                aspect_ratio,
+                PhysicalSettings {
+                    focal_length_mm,
+                    sensor_mm: (sensor_mm[0], sensor_mm[1]),
+                    f_stop,
+                    shutter_s,
+                    iso,
+                    focus_distance: self.focus_distance(),
+                    aperture: aperture.build()?,
+                    cat_eye,
+                },
+            )),
+        };
+        Ok(camera)
+    }
+}
@@ -0,0 +1,151 @@
+use std::mem::discriminant;
+
+use serde::Deserialize;
+
+use super::animation::track::Interpolate;
+use crate::{
+    materials::{
+        conductor::Conductor, dielectric::Dielectric, lambertian::Lambertian,
+        material::MaterialType, metal::Metal, rough_dielectric::RoughDielectric,
+    },
+    util::color::Color,
+};
+
+/**
+ * Material as found in scene files
+ */
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MaterialDescription {
+    Lambertian {
+        albedo: [f32; 3],
+    },
+    Metal {
+        albedo: [f32; 3],
+        fuzziness: f32,
+    },
+    Dielectric {
+        refraction_index: f32,
+    },
+    Conductor {
+        eta: [f32; 3],
+        k: [f32; 3],
+        roughness: f32,
+    },
+    RoughDielectric {
+        refraction_index: f32,
+        roughness: f32,
+    },
+}
+
+impl MaterialDescription {
+    pub fn build(&self) -> MaterialType {
+        match *self {
+            MaterialDescription::Lambertian { albedo } => MaterialType::Lambertian(Lambertian {
+                albedo: Color::new(albedo),
+            }),
+            MaterialDescription::Metal { albedo, fuzziness } => MaterialType::Metal(Metal {
+                albedo: Color::new(albedo),
+                fuzziness,
+            }),
+            MaterialDescription::Dielectric { refraction_index } => {
+                MaterialType::Dielectric(Dielectric {
+                    refraction_index,
+                    dispersion: None,
+                })
+            }
+            MaterialDescription::Conductor { eta, k, roughness } => {
+                MaterialType::Conductor(Conductor {
+                    eta: Color::new(eta),
+                    k: Color::new(k),
+                    roughness,
+                })
+            }
+            MaterialDescription::RoughDielectric {
+                refraction_index,
+                roughness,
+            } => MaterialType::RoughDielectric(RoughDielectric {
+                refraction_index,
+                roughness,
+            }),
+        }
+    }
+
+    /**
+     * Numeric parameters in declaration order of the variant
+     */
+    fn parameters(&self) -> Vec<f32> {
+        match *self {
+            MaterialDescription::Lambertian { albedo } => albedo.to_vec(),
+            MaterialDescription::Metal { albedo, fuzziness } => {
+                [albedo.as_slice(), &[fuzziness]].concat()
+            }
+            MaterialDescription::Dielectric { refraction_index } => vec![refraction_index],
+            MaterialDescription::Conductor { eta, k, roughness } => {
+                [eta.as_slice(), &k, &[roughness]].concat()
+            }
+            MaterialDescription::RoughDielectric {
+                refraction_index,
+                roughness,
+            } => vec![refraction_index, roughness],
+        }
+    }
+
+    /**
+     * Same kind of material with parameters in order of parameters(), clamped to valid ranges
+     * as Bézier keys can overshoot
+     */
+    fn with_parameters(&self, values: &[f32]) -> Self {
+        let color = |offset: usize| [0, 1, 2].map(|channel| values[offset + channel].max(0.0));
+        match self {
+            MaterialDescription::Lambertian { .. } => {
+                MaterialDescription::Lambertian { albedo: color(0) }
+            }
+            MaterialDescription::Metal { .. } => MaterialDescription::Metal {
+                albedo: color(0),
+                fuzziness: values[3].clamp(0.0, 1.0),
+            },
+            MaterialDescription::Dielectric { .. } => MaterialDescription::Dielectric {
+                refraction_index: values[0].max(f32::EPSILON),
+            },
+            MaterialDescription::Conductor { .. } => MaterialDescription::Conductor {
+                eta: color(0),
+                k: color(3),
+                roughness: values[6].clamp(0.0, 1.0),
+            },
+            MaterialDescription::RoughDielectric { .. } => MaterialDescription::RoughDielectric {
+                refraction_index: values[0].max(f32::EPSILON),
+                roughness: values[1].clamp(0.0, 1.0),
+            },
+        }
+    }
+}
+
+/**
+ * Parameters of keys of the same kind are interpolated. Between keys of different kinds the
+ * kind of the heavier key is used, blended only with the keys sharing it.
+ */
+impl Interpolate for MaterialDescription {
+    fn weighted_sum(terms: &[(&Self, f32)]) -> Self {
+        let (dominant, _) = terms
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .expect("weighted sum of no materials");
+        let same_kind: Vec<_> = terms
+            .iter()
+            .filter(|(material, _)| discriminant(*material) == discriminant(*dominant))
+            .collect();
+        let total: f32 = same_kind.iter().map(|(_, weight)| weight).sum();
+        if total.abs() < f32::EPSILON {
+            return (*dominant).clone();
+        }
+
+        let mut sum = vec![0.0; dominant.parameters().len()];
+        for (material, weight) in same_kind {
+            for (sum, value) in sum.iter_mut().zip(material.parameters()) {
+                *sum += value * weight / total;
+            }
+        }
+        dominant.with_parameters(&sum)
+    }
+}
@@ -1,70 +1,156 @@
-use std::{f32::consts::PI, rc::Rc};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
-use super::camera::Camera;
+use super::{
+    animation::ObjectAnimation,
+    builder::SceneBuilder,
+    camera_description::{CameraDescription, Projection},
+    scene_description::SceneDescription,
+};
 use crate::{
     materials::{
         dielectric::Dielectric, lambertian::Lambertian, material::MaterialType, metal::Metal,
     },
-    math::vec3::Vec3,
-    shapes::{hit_collection::HitCollection, sphere::Sphere},
+    shapes::{animated::Animated, hit_collection::HitCollection},
     util::{color::Color, point::Point},
 };
 
 pub struct Scene {
-    pub camera: Camera,
+    /// Named camera presets, first one is used unless another is picked by name
+    pub cameras: Vec<CameraDescription>,
     pub world: HitCollection,
 }
 
-impl Default for Scene {
-    fn default() -> Self {
-        // World
-        let mut world = HitCollection::default();
-        world.add(Sphere::new_boxed(
-            Point::new([0.0, -100.5, -1.0]),
-            100.0,
-            MaterialType::Lambertian(Lambertian {
-                albedo: Color::new([0.8, 0.8, 0.8]),
-            }),
-        ));
-        world.add(Sphere::new_boxed(
-            Point::new([-1.0, 0.0, -1.0]),
-            0.5,
-            MaterialType::Dielectric(Dielectric {
-                refraction_index: 1.5,
-            }),
-        ));
-        world.add(Sphere::new_boxed(
-            Point::new([0.0, 0.0, -1.0]),
-            0.5,
+impl Scene {
+    pub fn builder() -> SceneBuilder {
+        SceneBuilder::new()
+    }
+
+    /**
+     * Makes objects follow their keyframes
+     */
+    pub fn animate(&mut self, animations: &[ObjectAnimation]) -> Result<(), String> {
+        for animation in animations {
+            self.world.wrap(animation.object, |object| {
+                Box::new(Animated::new(object, animation))
+            })?;
+        }
+        Ok(())
+    }
+
+    /**
+     * Final scene of Ray Tracing in One Weekend: few hundred small spheres with random
+     * materials around three large ones. Same seed always produces the same scene.
+     */
+    pub fn random(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut builder = Scene::builder().with_sphere(
+            Point::new([0.0, -1000.0, 0.0]),
+            1000.0,
             MaterialType::Lambertian(Lambertian {
-                albedo: Color::new([0.7, 0.3, 0.3]),
-            }),
-        ));
-        world.add(Sphere::new_boxed(
-            Point::new([1.0, 0.0, -1.0]),
-            0.5,
-            MaterialType::Metal(Metal {
-                albedo: Color::new([0.8, 0.6, 0.2]),
-                fuzziness: 1.0,
+                albedo: Color::new([0.5, 0.5, 0.5]),
             }),
-        ));
-
-        // Camera
-        let look_from = Point::new([3.0, 3.0, 2.0]);
-        let look_at = Point::new([0.0, 0.0, -1.0]);
-        let view_up = Vec3::new([0.0, 1.0, 0.0]);
-        let dist_to_focus = (look_from - look_at).length();
-        let aperture = 2.0;
-        let camera = Camera::new(
-            look_from,
-            look_at,
-            view_up,
-            PI / 180.0 * 20.0,
-            16.0 / 9.0,
-            aperture,
-            dist_to_focus,
         );
 
-        Scene { camera, world }
+        for a in -11..11 {
+            for b in -11..11 {
+                let center = Point::new([
+                    a as f32 + 0.9 * rng.gen::<f32>(),
+                    0.2,
+                    b as f32 + 0.9 * rng.gen::<f32>(),
+                ]);
+                if (center - Point::new([4.0, 0.2, 0.0])).length() <= 0.9 {
+                    continue;
+                }
+
+                let material = match rng.gen::<f32>() {
+                    choice if choice < 0.8 => MaterialType::Lambertian(Lambertian {
+                        albedo: random_color(&mut rng, 0.0, 1.0) * random_color(&mut rng, 0.0, 1.0),
+                    }),
+                    choice if choice < 0.95 => MaterialType::Metal(Metal {
+                        albedo: random_color(&mut rng, 0.5, 1.0),
+                        fuzziness: rng.gen_range(0.0..0.5),
+                    }),
+                    _ => MaterialType::Dielectric(Dielectric {
+                        refraction_index: 1.5,
+                        dispersion: None,
+                    }),
+                };
+                builder = builder.with_sphere(center, 0.2, material);
+            }
+        }
+
+        builder
+            .with_sphere(
+                Point::new([0.0, 1.0, 0.0]),
+                1.0,
+                MaterialType::Dielectric(Dielectric {
+                    refraction_index: 1.5,
+                    dispersion: None,
+                }),
+            )
+            .with_sphere(
+                Point::new([-4.0, 1.0, 0.0]),
+                1.0,
+                MaterialType::Lambertian(Lambertian {
+                    albedo: Color::new([0.4, 0.2, 0.1]),
+                }),
+            )
+            .with_sphere(
+                Point::new([4.0, 1.0, 0.0]),
+                1.0,
+                MaterialType::Metal(Metal {
+                    albedo: Color::new([0.7, 0.6, 0.5]),
+                    fuzziness: 0.0,
+                }),
+            )
+            .with_camera(CameraDescription {
+                look_from: [13.0, 2.0, 3.0],
+                look_at: [0.0, 0.0, 0.0],
+                aperture: 0.1,
+                focus_distance: Some(10.0),
+                ..Default::default()
+            })
+            .build()
+    }
+
+    pub fn default_cameras() -> Vec<CameraDescription> {
+        vec![
+            CameraDescription::default(),
+            CameraDescription {
+                name: "front".to_string(),
+                look_from: [0.0, 0.5, 2.0],
+                fov: 35.0,
+                aperture: 0.0,
+                ..Default::default()
+            },
+            CameraDescription {
+                name: "top".to_string(),
+                look_from: [0.0, 5.0, -1.0],
+                up: [0.0, 0.0, -1.0],
+                aperture: 0.0,
+                projection: Projection::Orthographic { view_height: 3.0 },
+                ..Default::default()
+            },
+            CameraDescription {
+                name: "panorama".to_string(),
+                look_from: [0.0, 0.3, 1.0],
+                aperture: 0.0,
+                projection: Projection::Equirectangular,
+                ..Default::default()
+            },
+        ]
     }
 }
+
+impl Default for Scene {
+    fn default() -> Self {
+        SceneDescription::default()
+            .builder()
+            .with_cameras(Scene::default_cameras())
+            .build()
+    }
+}
+
+fn random_color(rng: &mut StdRng, min: f32, max: f32) -> Color {
+    Color::new([(); 3].map(|_| rng.gen_range(min..max)))
+}
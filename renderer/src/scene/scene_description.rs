@@ -0,0 +1,101 @@
+use super::{builder::SceneBuilder, material_description::MaterialDescription, scene::Scene};
+use crate::util::point::Point;
+
+#[derive(Clone, Debug)]
+pub struct SphereDescription {
+    pub name: String,
+    pub center: [f32; 3],
+    pub radius: f32,
+    /// Index into the materials of the scene
+    pub material: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct NamedMaterial {
+    pub name: String,
+    pub material: MaterialDescription,
+}
+
+/**
+ * Scene of named spheres sharing materials by index, editable before it is built
+ */
+#[derive(Clone, Debug)]
+pub struct SceneDescription {
+    pub spheres: Vec<SphereDescription>,
+    pub materials: Vec<NamedMaterial>,
+}
+
+impl Default for SceneDescription {
+    /**
+     * Ground with glass, diffuse and metal spheres side by side, the objects of Scene::default
+     */
+    fn default() -> Self {
+        let sphere = |name: &str, center, radius, material| SphereDescription {
+            name: name.to_string(),
+            center,
+            radius,
+            material,
+        };
+        let material = |name: &str, material| NamedMaterial {
+            name: name.to_string(),
+            material,
+        };
+
+        Self {
+            spheres: vec![
+                sphere("Ground", [0.0, -100.5, -1.0], 100.0, 0),
+                sphere("Left", [-1.0, 0.0, -1.0], 0.5, 1),
+                sphere("Center", [0.0, 0.0, -1.0], 0.5, 2),
+                sphere("Right", [1.0, 0.0, -1.0], 0.5, 3),
+            ],
+            materials: vec![
+                material(
+                    "Ground",
+                    MaterialDescription::Lambertian {
+                        albedo: [0.8, 0.8, 0.8],
+                    },
+                ),
+                material(
+                    "Glass",
+                    MaterialDescription::Dielectric {
+                        refraction_index: 1.5,
+                    },
+                ),
+                material(
+                    "Red diffuse",
+                    MaterialDescription::Lambertian {
+                        albedo: [0.7, 0.3, 0.3],
+                    },
+                ),
+                material(
+                    "Gold metal",
+                    MaterialDescription::Metal {
+                        albedo: [0.8, 0.6, 0.2],
+                        fuzziness: 1.0,
+                    },
+                ),
+            ],
+        }
+    }
+}
+
+impl SceneDescription {
+    /**
+     * Builder holding the spheres, cameras can still be added
+     */
+    pub fn builder(&self) -> SceneBuilder {
+        self.spheres
+            .iter()
+            .fold(Scene::builder(), |builder, sphere| {
+                builder.with_sphere(
+                    Point::new(sphere.center),
+                    sphere.radius,
+                    self.materials[sphere.material].material.build(),
+                )
+            })
+    }
+
+    pub fn build(&self) -> Scene {
+        self.builder().build()
+    }
+}
@@ -0,0 +1,30 @@
+use std::{fs, path::Path};
+
+use serde::Deserialize;
+
+use super::{animation::Timeline, camera_description::CameraDescription};
+
+/**
+ * Scene description loaded from JSON, for example
+ * {"cameras": [{"name": "front", "look_from": [0, 1, 3], "fov": 40, "aperture": 0.0}]}
+ */
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SceneFile {
+    pub cameras: Vec<CameraDescription>,
+    pub animation: Timeline,
+}
+
+impl SceneFile {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("could not read scene file {}: {}", path.display(), err))?;
+        let scene_file: SceneFile = serde_json::from_str(&content)
+            .map_err(|err| format!("invalid scene file {}: {}", path.display(), err))?;
+        scene_file
+            .animation
+            .validate()
+            .map_err(|err| format!("invalid scene file {}: {}", path.display(), err))?;
+        Ok(scene_file)
+    }
+}
@@ -1,4 +1,6 @@
+pub mod animated;
 pub mod hit_collection;
 pub mod hit_record;
 pub mod sphere;
 pub mod traits;
+pub mod triangle;
@@ -0,0 +1,145 @@
+use std::sync::RwLock;
+
+use crate::{
+    materials::material::MaterialType,
+    math::vec3::Vec3,
+    scene::{
+        animation::{track::Track, ObjectAnimation},
+        material_description::MaterialDescription,
+    },
+    util::{point::Point, ray::Ray},
+};
+
+use super::{hit_record::HitRecord, traits::Hit};
+
+/// Smallest scale used, Bézier keys can overshoot to zero and below
+const MIN_SCALE: f32 = 1e-4;
+
+/**
+ * Object whose transform and material follow keyframes, evaluated at the time carried by the ray
+ */
+pub struct Animated {
+    pub object: Box<dyn Hit + Sync>,
+    pub pivot: Point,
+    pub translation: Track<[f32; 3]>,
+    /// Euler angles in degrees
+    pub rotation: Track<[f32; 3]>,
+    pub scale: Track<f32>,
+    pub material: Track<MaterialDescription>,
+    /// Material built for the last time it was sampled at, rays of a frame share their time
+    frame_material: RwLock<Option<(f32, MaterialType)>>,
+}
+
+/**
+ * Object to world transform at a single point in time
+ */
+struct Transform {
+    pivot: Point,
+    translation: Vec3,
+    /// Sines and cosines of x, y and z rotation
+    rotation: [(f32, f32); 3],
+    scale: f32,
+}
+
+impl Transform {
+    fn point_to_world(&self, point: &Point) -> Point {
+        self.pivot + self.translation + self.rotate(&(point - self.pivot)) * self.scale
+    }
+
+    fn point_to_local(&self, point: &Point) -> Point {
+        self.pivot + self.unrotate(&(point - self.pivot - self.translation)) / self.scale
+    }
+
+    fn rotate(&self, vec: &Vec3) -> Vec3 {
+        self.rotation
+            .iter()
+            .enumerate()
+            .fold(*vec, |vec, (axis, &(sin, cos))| {
+                rotate_axis(&vec, axis, sin, cos)
+            })
+    }
+
+    fn unrotate(&self, vec: &Vec3) -> Vec3 {
+        self.rotation
+            .iter()
+            .enumerate()
+            .rev()
+            .fold(*vec, |vec, (axis, &(sin, cos))| {
+                rotate_axis(&vec, axis, -sin, cos)
+            })
+    }
+}
+
+/**
+ * Rotation around x (0), y (1) or z (2) axis
+ */
+fn rotate_axis(vec: &Vec3, axis: usize, sin: f32, cos: f32) -> Vec3 {
+    let (a, b) = ((axis + 1) % 3, (axis + 2) % 3);
+    let mut rotated = vec.e;
+    rotated[a] = cos * vec.e[a] - sin * vec.e[b];
+    rotated[b] = sin * vec.e[a] + cos * vec.e[b];
+    Vec3::new(rotated)
+}
+
+impl Animated {
+    pub fn new(object: Box<dyn Hit + Sync>, animation: &ObjectAnimation) -> Self {
+        Self {
+            object,
+            pivot: Point::new(animation.pivot),
+            translation: animation.translation.clone(),
+            rotation: animation.rotation.clone(),
+            scale: animation.scale.clone(),
+            material: animation.material.clone(),
+            frame_material: RwLock::new(None),
+        }
+    }
+
+    fn transform(&self, time: f32) -> Transform {
+        let rotation = self.rotation.sample(time).unwrap_or_default();
+        Transform {
+            pivot: self.pivot,
+            translation: Vec3::new(self.translation.sample(time).unwrap_or_default()),
+            rotation: rotation.map(|degrees| degrees.to_radians().sin_cos()),
+            scale: self.scale.sample(time).unwrap_or(1.0).max(MIN_SCALE),
+        }
+    }
+
+    /**
+     * Material interpolated at time, built once and reused while the time stays the same
+     */
+    fn material(&self, time: f32) -> Option<MaterialType> {
+        if self.material.is_empty() {
+            return None;
+        }
+        if let Some((built_at, material)) = &*self.frame_material.read().unwrap() {
+            if *built_at == time {
+                return Some(material.clone());
+            }
+        }
+
+        let material = self.material.sample(time)?.build();
+        *self.frame_material.write().unwrap() = Some((time, material.clone()));
+        Some(material)
+    }
+}
+
+impl Hit for Animated {
+    fn hit(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<HitRecord> {
+        let transform = self.transform(ray.time());
+
+        // Same ray parameter t in both spaces as the transform is affine
+        let local_ray = ray.with_geometry(
+            transform.point_to_local(&ray.origin()),
+            transform.unrotate(&ray.direction()) / transform.scale,
+        );
+        let record = self.object.hit(&local_ray, t_min, t_max)?;
+
+        Some(HitRecord {
+            point: transform.point_to_world(&record.point),
+            normal: transform.rotate(&record.normal),
+            tangent: transform.rotate(&record.tangent),
+            material: self.material(ray.time()).unwrap_or(record.material),
+            ..record
+        })
+    }
+}
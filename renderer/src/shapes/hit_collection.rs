@@ -1,7 +1,20 @@
+use std::cell::Cell;
+
 use crate::util::ray::Ray;
 
 use super::{hit_record::HitRecord, traits::Hit};
 
+thread_local! {
+    static INTERSECTION_TESTS: Cell<u64> = const { Cell::new(0) };
+}
+
+/**
+ * Objects tested against rays by collections on the calling thread since it started
+ */
+pub fn intersection_tests() -> u64 {
+    INTERSECTION_TESTS.with(|tests| tests.get())
+}
+
 pub struct HitCollection {
     hittables: Vec<Box<dyn Hit + Sync>>,
 }
@@ -15,8 +28,9 @@ impl Default for HitCollection {
 impl Hit for HitCollection {
     fn hit(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<HitRecord> {
         let mut closest_hit_record: Option<HitRecord> = None;
+        INTERSECTION_TESTS.with(|tests| tests.set(tests.get() + self.hittables.len() as u64));
 
-        for hittable in &self.hittables {
+        for (object_id, hittable) in self.hittables.iter().enumerate() {
             let closest_distance = match &closest_hit_record {
                 Some(record) => record.t,
                 None => t_max,
@@ -25,7 +39,10 @@ impl Hit for HitCollection {
             let record = hittable.hit(ray, t_min, closest_distance);
             match record {
                 Some(record) => {
-                    closest_hit_record = Some(record);
+                    closest_hit_record = Some(HitRecord {
+                        object_id,
+                        ..record
+                    });
                 }
                 None => continue,
             }
@@ -39,4 +56,25 @@ impl HitCollection {
     pub fn add(&mut self, hittable: Box<dyn Hit + Sync>) {
         self.hittables.push(hittable)
     }
+
+    /**
+     * Wraps object at index, keeping its index so object IDs stay stable
+     */
+    pub fn wrap(
+        &mut self,
+        index: usize,
+        wrapper: impl FnOnce(Box<dyn Hit + Sync>) -> Box<dyn Hit + Sync>,
+    ) -> Result<(), String> {
+        if index >= self.hittables.len() {
+            return Err(format!(
+                "object index {} out of range, scene has {} objects",
+                index,
+                self.hittables.len()
+            ));
+        }
+
+        let object = self.hittables.remove(index);
+        self.hittables.insert(index, wrapper(object));
+        Ok(())
+    }
 }
@@ -1,17 +1,48 @@
 use crate::{
     materials::material::MaterialType,
-    math::vec3::Vec3,
+    math::{onb::Onb, vec3::Vec3},
     util::{point::Point, ray::Ray},
 };
 
+#[derive(Clone)]
 pub struct HitRecord {
     pub point: Point,
     pub normal: Vec3,
+    /// Unit surface tangent along increasing u, perpendicular to normal. Flipped with the
+    /// normal on back faces.
+    pub tangent: Vec3,
     pub t: f32,
+    pub u: f32,
+    pub v: f32,
     pub front_face: bool,
     pub material: MaterialType,
+    /// Index of hit object in its collection
+    pub object_id: usize,
 }
 
 pub fn is_front_face(ray: &Ray, outward_normal: &Vec3) -> bool {
     ray.direction().dot(outward_normal) < 0.0
 }
+
+/**
+ * Normal and tangent turned to the side the ray arrives from. Both flip on back faces so the
+ * shading frame stays right handed.
+ */
+pub fn face_forward(outward_normal: Vec3, tangent: Vec3, front_face: bool) -> (Vec3, Vec3) {
+    match front_face {
+        true => (outward_normal, tangent),
+        false => (-outward_normal, -tangent),
+    }
+}
+
+/**
+ * Gram-Schmidt tangent against normal. Falls back to any perpendicular vector when degenerate.
+ */
+pub fn orthogonal_tangent(tangent: &Vec3, normal: &Vec3) -> Vec3 {
+    let orthogonal = tangent - normal * normal.dot(tangent);
+    if orthogonal.length_squared() > f32::EPSILON {
+        return orthogonal.unit_vector();
+    }
+
+    Onb::from_w(normal).u
+}
@@ -1,7 +1,9 @@
 use super::{
-    hit_record::{is_front_face, HitRecord},
+    hit_record::{face_forward, is_front_face, orthogonal_tangent, HitRecord},
     traits::Hit,
 };
+use std::f32::consts::PI;
+
 use crate::{materials::material::MaterialType, math::vec3::Vec3, util::ray::Ray};
 
 pub struct Sphere {
@@ -24,19 +26,25 @@ impl Sphere {
     }
 
     fn root_to_hit_record(&self, root: f32, ray: &Ray) -> HitRecord {
-        let point = ray.at(root);
-        let normal = (point - self.center) / self.radius;
-        let is_front_face = is_front_face(&ray, &normal);
+        // Projected back onto the surface, ray.at carries rounding error of the ray origin
+        let normal = (ray.at(root) - self.center).unit_vector();
+        let point = self.center + normal * self.radius;
+        let is_front_face = is_front_face(ray, &normal);
+        let (u, v) = sphere_uv(&normal);
+        // Derivative of point with respect to u, undefined at the poles
+        let tangent = orthogonal_tangent(&Vec3::new([normal.z(), 0.0, -normal.x()]), &normal);
+        let (normal, tangent) = face_forward(normal, tangent, is_front_face);
 
         HitRecord {
             point,
-            normal: match is_front_face {
-                true => normal,
-                false => -normal,
-            },
+            normal,
+            tangent,
             t: root,
+            u,
+            v,
             front_face: is_front_face,
             material: self.material.clone(),
+            object_id: 0,
         }
     }
 }
@@ -44,29 +52,42 @@ impl Sphere {
 impl Hit for Sphere {
     fn hit(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<HitRecord> {
         let oc = ray.origin() - self.center;
+        let direction = ray.direction();
 
-        let a = ray.direction().length().powi(2);
-        let half_b = oc.dot(&ray.direction());
-        let c = oc.length().powi(2) - self.radius * self.radius;
+        let a = direction.length_squared();
+        let half_b = oc.dot(&direction);
+        let c = oc.length_squared() - self.radius * self.radius;
 
-        let discriminant = half_b * half_b - a * c;
+        // Discriminant from distance between center and the ray's line, half_b² - ac cancels
+        // catastrophically for small or distant spheres (Ray Tracing Gems, chapter 7)
+        let perpendicular = oc - direction * (half_b / a);
+        let discriminant = a * (self.radius * self.radius - perpendicular.length_squared());
         if discriminant < 0.0 {
             return None;
         }
 
-        // Find nearest root in range from t_min to t_max
-        let discriminant_sqrt = discriminant.sqrt();
+        // Stable form of both roots, avoids subtracting nearly equal values
+        let q = -(half_b + discriminant.sqrt().copysign(half_b));
+        let (root1, root2) = match q == 0.0 {
+            true => (0.0, 0.0),
+            false => (q / a, c / q),
+        };
 
-        let root = (-half_b - discriminant_sqrt) / a;
-        if t_min <= root && root <= t_max {
-            return Some(self.root_to_hit_record(root, ray));
-        }
+        // Find nearest root in range from t_min to t_max
+        let (near, far) = (root1.min(root2), root1.max(root2));
+        [near, far]
+            .into_iter()
+            .find(|root| t_min <= *root && *root <= t_max)
+            .map(|root| self.root_to_hit_record(root, ray))
+    }
+}
 
-        let root = (-half_b + discriminant_sqrt) / a;
-        if t_min <= root && root <= t_max {
-            return Some(self.root_to_hit_record(root, ray));
-        }
+/**
+ * UV coordinates of point on unit sphere. u goes around Y axis starting from -X, v from bottom to top.
+ */
+fn sphere_uv(point: &Vec3) -> (f32, f32) {
+    let theta = (-point.y()).acos();
+    let phi = (-point.z()).atan2(point.x()) + PI;
 
-        None
-    }
+    (phi / (2.0 * PI), theta / PI)
 }
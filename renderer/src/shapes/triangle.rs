@@ -0,0 +1,117 @@
+use super::{
+    hit_record::{face_forward, is_front_face, orthogonal_tangent, HitRecord},
+    traits::Hit,
+};
+use crate::{
+    materials::material::MaterialType,
+    math::vec3::Vec3,
+    util::{point::Point, ray::Ray},
+};
+
+/**
+ * Single mesh face. Vertices are in counter clockwise order when looking at the front side.
+ */
+pub struct Triangle {
+    pub vertices: [Point; 3],
+    pub uvs: [[f32; 2]; 3],
+    pub material: MaterialType,
+    normal: Vec3,
+    tangent: Vec3,
+}
+
+impl Triangle {
+    pub fn new(vertices: [Point; 3], uvs: [[f32; 2]; 3], material: MaterialType) -> Self {
+        let edge1 = vertices[1] - vertices[0];
+        let edge2 = vertices[2] - vertices[0];
+        let normal = edge1.cross(&edge2).unit_vector();
+
+        let uv_edge1 = [uvs[1][0] - uvs[0][0], uvs[1][1] - uvs[0][1]];
+        let uv_edge2 = [uvs[2][0] - uvs[0][0], uvs[2][1] - uvs[0][1]];
+        let tangent = tangent_from_uvs([edge1, edge2], [uv_edge1, uv_edge2], &normal);
+
+        Self {
+            vertices,
+            uvs,
+            material,
+            normal,
+            tangent,
+        }
+    }
+
+    pub fn new_boxed(
+        vertices: [Point; 3],
+        uvs: [[f32; 2]; 3],
+        material: MaterialType,
+    ) -> Box<Self> {
+        Box::new(Self::new(vertices, uvs, material))
+    }
+}
+
+impl Hit for Triangle {
+    /**
+     * Möller-Trumbore intersection
+     */
+    fn hit(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<HitRecord> {
+        let edge1 = self.vertices[1] - self.vertices[0];
+        let edge2 = self.vertices[2] - self.vertices[0];
+
+        let p = ray.direction().cross(&edge2);
+        let determinant = edge1.dot(&p);
+        if determinant.abs() < f32::EPSILON {
+            return None;
+        }
+        let inverse_determinant = 1.0 / determinant;
+
+        let s = ray.origin() - self.vertices[0];
+        let b1 = s.dot(&p) * inverse_determinant;
+        if !(0.0..=1.0).contains(&b1) {
+            return None;
+        }
+
+        let q = s.cross(&edge1);
+        let b2 = ray.direction().dot(&q) * inverse_determinant;
+        if b2 < 0.0 || b1 + b2 > 1.0 {
+            return None;
+        }
+
+        let t = edge2.dot(&q) * inverse_determinant;
+        if t < t_min || t > t_max {
+            return None;
+        }
+
+        let b0 = 1.0 - b1 - b2;
+        let u = b0 * self.uvs[0][0] + b1 * self.uvs[1][0] + b2 * self.uvs[2][0];
+        let v = b0 * self.uvs[0][1] + b1 * self.uvs[1][1] + b2 * self.uvs[2][1];
+        let front_face = is_front_face(ray, &self.normal);
+        let (normal, tangent) = face_forward(self.normal, self.tangent, front_face);
+
+        Some(HitRecord {
+            point: ray.at(t),
+            normal,
+            tangent,
+            t,
+            u,
+            v,
+            front_face,
+            material: self.material.clone(),
+            object_id: 0,
+        })
+    }
+}
+
+/**
+ * Tangent along increasing u, solved from edge vectors and their UV deltas
+ */
+fn tangent_from_uvs(edges: [Vec3; 2], uv_edges: [[f32; 2]; 2], normal: &Vec3) -> Vec3 {
+    let [edge1, edge2] = edges;
+    let [[du1, dv1], [du2, dv2]] = uv_edges;
+
+    let determinant = du1 * dv2 - du2 * dv1;
+    let tangent = if determinant.abs() > f32::EPSILON {
+        (edge1 * dv2 - edge2 * dv1) / determinant
+    } else {
+        edge1
+    };
+
+    orthogonal_tangent(&tangent, normal)
+}
@@ -0,0 +1,64 @@
+use std::sync::OnceLock;
+
+use super::sampled::{LAMBDA_MAX, LAMBDA_MIN};
+use crate::{math::vec3::Vec3, util::color::Color};
+
+/**
+ * Integral of Y matching function over wavelength, used to normalize luminance
+ */
+pub const CIE_Y_INTEGRAL: f32 = 106.856_895;
+
+/**
+ * Multi-lobe Gaussian fits of CIE 1931 matching functions (Wyman et al. 2013)
+ */
+pub fn x_bar(lambda: f32) -> f32 {
+    1.056 * lobe(lambda, 599.8, 37.9, 31.0) + 0.362 * lobe(lambda, 442.0, 16.0, 26.7)
+        - 0.065 * lobe(lambda, 501.1, 20.4, 26.2)
+}
+
+pub fn y_bar(lambda: f32) -> f32 {
+    0.821 * lobe(lambda, 568.8, 46.9, 40.5) + 0.286 * lobe(lambda, 530.9, 16.3, 31.1)
+}
+
+pub fn z_bar(lambda: f32) -> f32 {
+    1.217 * lobe(lambda, 437.0, 11.8, 36.0) + 0.681 * lobe(lambda, 459.0, 26.0, 13.8)
+}
+
+/**
+ * Piecewise Gaussian with different deviation on each side of the mean
+ */
+fn lobe(lambda: f32, mean: f32, sigma_low: f32, sigma_high: f32) -> f32 {
+    let sigma = if lambda < mean { sigma_low } else { sigma_high };
+    let t = (lambda - mean) / sigma;
+    (-0.5 * t * t).exp()
+}
+
+/**
+ * CIE XYZ to linear sRGB (D65)
+ */
+pub fn xyz_to_rgb(xyz: &Color) -> Color {
+    let (x, y, z) = (xyz.x(), xyz.y(), xyz.z());
+    Color::new([
+        3.240_454 * x - 1.537_138 * y - 0.498_531 * z,
+        -0.969_266 * x + 1.876_011 * y + 0.041_556 * z,
+        0.055_643 * x - 0.204_026 * y + 1.057_225 * z,
+    ])
+}
+
+/**
+ * Linear sRGB of a flat spectrum with value 1. Dividing by this keeps white surfaces white.
+ */
+pub fn equal_energy_white() -> Color {
+    static WHITE: OnceLock<Color> = OnceLock::new();
+
+    *WHITE.get_or_init(|| {
+        let mut xyz = Vec3::default();
+        let mut lambda = LAMBDA_MIN;
+        while lambda <= LAMBDA_MAX {
+            xyz = xyz + Vec3::new([x_bar(lambda), y_bar(lambda), z_bar(lambda)]);
+            lambda += 1.0;
+        }
+
+        xyz_to_rgb(&(xyz / CIE_Y_INTEGRAL))
+    })
+}
@@ -0,0 +1,134 @@
+use std::ops::{Add, Mul};
+
+use super::cie::{equal_energy_white, x_bar, xyz_to_rgb, y_bar, z_bar, CIE_Y_INTEGRAL};
+use crate::{math::vec3::Vec3, util::color::Color};
+
+pub const LAMBDA_MIN: f32 = 360.0;
+pub const LAMBDA_MAX: f32 = 830.0;
+
+/**
+ * Amount of wavelengths traced together with each camera ray
+ */
+pub const WAVELENGTH_SAMPLES: usize = 4;
+
+/**
+ * Hero wavelength sampling (Wilkie et al. 2014). First wavelength is the hero, others are
+ * spaced evenly over the visible range and dropped when a path turns wavelength dependent.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct SampledWavelengths {
+    lambda: [f32; WAVELENGTH_SAMPLES],
+    pdf: [f32; WAVELENGTH_SAMPLES],
+}
+
+impl SampledWavelengths {
+    /**
+     * u is uniform random number in range [0, 1)
+     */
+    pub fn sample_uniform(u: f32) -> Self {
+        let range = LAMBDA_MAX - LAMBDA_MIN;
+        let hero = LAMBDA_MIN + u * range;
+        let step = range / WAVELENGTH_SAMPLES as f32;
+
+        let lambda = std::array::from_fn(|i| {
+            let lambda = hero + i as f32 * step;
+            match lambda > LAMBDA_MAX {
+                true => lambda - range,
+                false => lambda,
+            }
+        });
+
+        Self {
+            lambda,
+            pdf: [1.0 / range; WAVELENGTH_SAMPLES],
+        }
+    }
+
+    pub fn hero(&self) -> f32 {
+        self.lambda[0]
+    }
+
+    pub fn lambda(&self) -> &[f32; WAVELENGTH_SAMPLES] {
+        &self.lambda
+    }
+
+    pub fn is_secondary_terminated(&self) -> bool {
+        self.pdf[1..].iter().all(|pdf| *pdf == 0.0)
+    }
+
+    /**
+     * Keeps only the hero wavelength. Its pdf is divided so the estimate stays unbiased.
+     */
+    pub fn terminate_secondary(&mut self) {
+        if self.is_secondary_terminated() {
+            return;
+        }
+
+        for pdf in self.pdf[1..].iter_mut() {
+            *pdf = 0.0;
+        }
+        self.pdf[0] /= WAVELENGTH_SAMPLES as f32;
+    }
+
+    /**
+     * Monte Carlo estimate of linear sRGB color for radiance sampled at these wavelengths.
+     * Result is white balanced so a flat spectrum gives neutral gray.
+     */
+    pub fn to_rgb(self, radiance: &SampledSpectrum) -> Color {
+        let mut xyz = Vec3::default();
+        for i in 0..WAVELENGTH_SAMPLES {
+            if self.pdf[i] == 0.0 {
+                continue;
+            }
+
+            let lambda = self.lambda[i];
+            let weight = radiance.values[i] / self.pdf[i];
+            xyz = xyz + Vec3::new([x_bar(lambda), y_bar(lambda), z_bar(lambda)]) * weight;
+        }
+
+        let rgb = xyz_to_rgb(&(xyz / (WAVELENGTH_SAMPLES as f32 * CIE_Y_INTEGRAL)));
+        rgb / equal_energy_white()
+    }
+}
+
+/**
+ * Spectral quantity at the wavelengths of SampledWavelengths
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct SampledSpectrum {
+    pub values: [f32; WAVELENGTH_SAMPLES],
+}
+
+impl SampledSpectrum {
+    pub fn new(value: f32) -> Self {
+        Self {
+            values: [value; WAVELENGTH_SAMPLES],
+        }
+    }
+}
+
+impl Default for SampledSpectrum {
+    fn default() -> Self {
+        Self::new(0.0)
+    }
+}
+
+impl Mul for SampledSpectrum {
+    type Output = SampledSpectrum;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self {
+            values: std::array::from_fn(|i| self.values[i] * rhs.values[i]),
+        }
+    }
+}
+
+impl Add for SampledSpectrum {
+    type Output = SampledSpectrum;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            values: std::array::from_fn(|i| self.values[i] + rhs.values[i]),
+        }
+    }
+}
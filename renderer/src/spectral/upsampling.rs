@@ -0,0 +1,32 @@
+use super::sampled::{SampledSpectrum, SampledWavelengths};
+use crate::util::color::Color;
+
+/**
+ * Upsamples RGB to a smooth spectrum as a mix of blue, green and red basis functions.
+ * The basis sums to one at every wavelength, so white maps to a flat spectrum and reflectances
+ * in range [0, 1] stay in range [0, 1].
+ */
+pub fn rgb_to_spectrum(rgb: &Color, wavelengths: &SampledWavelengths) -> SampledSpectrum {
+    SampledSpectrum {
+        values: wavelengths.lambda().map(|lambda| {
+            let (blue, green, red) = basis(lambda);
+            rgb.z() * blue + rgb.y() * green + rgb.x() * red
+        }),
+    }
+}
+
+fn basis(lambda: f32) -> (f32, f32, f32) {
+    let blue_to_green = smoothstep(480.0, 510.0, lambda);
+    let green_to_red = smoothstep(575.0, 605.0, lambda);
+
+    (
+        1.0 - blue_to_green,
+        blue_to_green - green_to_red,
+        green_to_red,
+    )
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
@@ -0,0 +1,24 @@
+use crate::util::{color::Color, point::Point};
+
+use super::texture::{Texture, TextureType};
+
+/**
+ * Alternates between two textures in UV space. Scale is the amount of squares per UV unit.
+ */
+#[derive(Clone)]
+pub struct Checker {
+    pub even: Box<TextureType>,
+    pub odd: Box<TextureType>,
+    pub scale: f32,
+}
+
+impl Texture for Checker {
+    fn value(&self, u: f32, v: f32, point: &Point) -> Color {
+        let parity = (u * self.scale).floor() as i32 + (v * self.scale).floor() as i32;
+
+        match parity.rem_euclid(2) {
+            0 => self.even.value(u, v, point),
+            _ => self.odd.value(u, v, point),
+        }
+    }
+}
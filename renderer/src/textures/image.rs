@@ -0,0 +1,153 @@
+use std::{
+    fs,
+    io::{Error, ErrorKind},
+    sync::Arc,
+};
+
+use shared::data::srgb_to_linear;
+
+use crate::util::{color::Color, point::Point};
+
+use super::texture::Texture;
+
+/**
+ * Nearest neighbour image lookup. Row 0 is the top of the image, v = 1.
+ */
+#[derive(Clone)]
+pub struct ImageTexture {
+    width: usize,
+    height: usize,
+    pixels: Arc<Vec<Color>>,
+}
+
+impl ImageTexture {
+    /**
+     * Panics when the image is empty or pixels don't fill it
+     */
+    pub fn new(width: usize, height: usize, pixels: Vec<Color>) -> Self {
+        assert!(width > 0 && height > 0, "image texture is empty");
+        assert_eq!(width * height, pixels.len());
+        Self {
+            width,
+            height,
+            pixels: Arc::new(pixels),
+        }
+    }
+
+    /**
+     * Loads P3 (ascii) or P6 (binary) PPM. Values are kept as stored, scaled to range [0, 1].
+     */
+    pub fn from_ppm(path: &str) -> Result<Self, Error> {
+        let bytes = fs::read(path)?;
+        let mut pos = 0;
+
+        let magic = next_token(&bytes, &mut pos)?;
+        let width = parse_number(next_token(&bytes, &mut pos)?)?;
+        let height = parse_number(next_token(&bytes, &mut pos)?)?;
+        let max_value = parse_number(next_token(&bytes, &mut pos)?)? as f32;
+        if width == 0 || height == 0 {
+            return Err(invalid_data("PPM image is empty"));
+        }
+
+        let values: Vec<f32> = match magic {
+            b"P3" => (0..width * height * 3)
+                .map(|_| Ok(parse_number(next_token(&bytes, &mut pos)?)? as f32 / max_value))
+                .collect::<Result<_, Error>>()?,
+            b"P6" => {
+                // Single whitespace separates header from raster
+                let raster = bytes.get(pos + 1..).unwrap_or_default();
+                let sample_size = if max_value < 256.0 { 1 } else { 2 };
+                if raster.len() < width * height * 3 * sample_size {
+                    return Err(invalid_data("PPM raster is truncated"));
+                }
+
+                raster
+                    .chunks(sample_size)
+                    .take(width * height * 3)
+                    .map(|sample| match sample {
+                        [value] => *value as f32 / max_value,
+                        [high, low] => u16::from_be_bytes([*high, *low]) as f32 / max_value,
+                        _ => unreachable!(),
+                    })
+                    .collect()
+            }
+            _ => return Err(invalid_data("Only P3 and P6 PPM images are supported")),
+        };
+
+        let pixels = values
+            .chunks(3)
+            .map(|rgb| Color::new([rgb[0], rgb[1], rgb[2]]))
+            .collect();
+
+        Ok(Self::new(width, height, pixels))
+    }
+
+    /**
+     * Converts sRGB encoded color images to linear values, the inverse of how frames are
+     * written. Data images (normal maps etc.) should be left as is.
+     */
+    pub fn decode_srgb(self) -> Self {
+        let pixels = self
+            .pixels
+            .iter()
+            .map(|pixel| Color::new(pixel.e.map(srgb_to_linear)))
+            .collect();
+        Self::new(self.width, self.height, pixels)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f32, v: f32, _: &Point) -> Color {
+        let u = u.clamp(0.0, 1.0);
+        let v = 1.0 - v.clamp(0.0, 1.0);
+
+        let i = ((u * self.width as f32) as usize).min(self.width - 1);
+        let j = ((v * self.height as f32) as usize).min(self.height - 1);
+
+        self.pixels[j * self.width + i]
+    }
+}
+
+fn next_token<'a>(bytes: &'a [u8], pos: &mut usize) -> Result<&'a [u8], Error> {
+    // Skip whitespace and comments
+    while *pos < bytes.len() {
+        match bytes[*pos] {
+            b'#' => {
+                while *pos < bytes.len() && bytes[*pos] != b'\n' {
+                    *pos += 1;
+                }
+            }
+            byte if byte.is_ascii_whitespace() => *pos += 1,
+            _ => break,
+        }
+    }
+
+    let start = *pos;
+    while *pos < bytes.len() && !bytes[*pos].is_ascii_whitespace() {
+        *pos += 1;
+    }
+
+    if start == *pos {
+        return Err(invalid_data("Unexpected end of PPM file"));
+    }
+    Ok(&bytes[start..*pos])
+}
+
+fn parse_number(token: &[u8]) -> Result<usize, Error> {
+    std::str::from_utf8(token)
+        .ok()
+        .and_then(|token| token.parse().ok())
+        .ok_or_else(|| invalid_data("Invalid number in PPM header"))
+}
+
+fn invalid_data(msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
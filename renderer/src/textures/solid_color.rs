@@ -0,0 +1,14 @@
+use crate::util::{color::Color, point::Point};
+
+use super::texture::Texture;
+
+#[derive(Clone)]
+pub struct SolidColor {
+    pub color: Color,
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _: f32, _: f32, _: &Point) -> Color {
+        self.color
+    }
+}
@@ -0,0 +1,44 @@
+use crate::util::{color::Color, point::Point};
+
+use super::{checker::Checker, image::ImageTexture, solid_color::SolidColor};
+
+#[derive(Clone)]
+pub enum TextureType {
+    Checker(Checker),
+    Image(ImageTexture),
+    SolidColor(SolidColor),
+}
+
+impl TextureType {
+    pub fn solid(color: Color) -> Self {
+        TextureType::SolidColor(SolidColor { color })
+    }
+
+    /**
+     * Uniform scalar texture, stored in every channel
+     */
+    pub fn constant(value: f32) -> Self {
+        Self::solid(Color::new([value, value, value]))
+    }
+}
+
+impl Texture for TextureType {
+    fn value(&self, u: f32, v: f32, point: &Point) -> Color {
+        match self {
+            TextureType::Checker(checker) => checker.value(u, v, point),
+            TextureType::Image(image) => image.value(u, v, point),
+            TextureType::SolidColor(solid) => solid.value(u, v, point),
+        }
+    }
+}
+
+pub trait Texture {
+    fn value(&self, u: f32, v: f32, point: &Point) -> Color;
+
+    /**
+     * Scalar parameters read the first channel
+     */
+    fn scalar(&self, u: f32, v: f32, point: &Point) -> f32 {
+        self.value(u, v, point).x()
+    }
+}
@@ -4,11 +4,44 @@ use crate::math::vec3::Vec3;
 pub struct Ray {
     origin: Vec3,
     direction: Vec3,
+    wavelength: Option<f32>,
+    /// Seconds from start of animation
+    time: f32,
 }
 
 impl Ray {
     pub fn new(origin: Vec3, direction: Vec3) -> Self {
-        Self { origin, direction }
+        Self {
+            origin,
+            direction,
+            wavelength: None,
+            time: 0.0,
+        }
+    }
+
+    /**
+     * Hero wavelength in nanometers carried by rays in spectral mode
+     */
+    pub fn with_wavelength(self, wavelength: f32) -> Self {
+        Self {
+            wavelength: Some(wavelength),
+            ..self
+        }
+    }
+
+    pub fn with_time(self, time: f32) -> Self {
+        Self { time, ..self }
+    }
+
+    /**
+     * Ray with new origin and direction keeping wavelength and time
+     */
+    pub fn with_geometry(&self, origin: Vec3, direction: Vec3) -> Self {
+        Self {
+            origin,
+            direction,
+            ..*self
+        }
     }
 
     pub fn origin(&self) -> Vec3 {
@@ -19,6 +52,14 @@ impl Ray {
         self.direction
     }
 
+    pub fn wavelength(&self) -> Option<f32> {
+        self.wavelength
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
     pub fn at(&self, t: f32) -> Vec3 {
         self.origin + (self.direction * t)
     }
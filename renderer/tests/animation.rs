@@ -0,0 +1,287 @@
+use std::{fs, process::Command};
+
+use renderer::{
+    materials::{lambertian::Lambertian, material::MaterialType},
+    math::vec3::Vec3,
+    scene::{
+        animation::{
+            track::{Interpolation, Keyframe, Track},
+            ObjectAnimation,
+        },
+        material_description::MaterialDescription,
+        scene_file::SceneFile,
+    },
+    shapes::{animated::Animated, sphere::Sphere, traits::Hit},
+    util::{color::Color, ray::Ray},
+};
+
+fn track<T>(keys: &[(f32, T)], interpolation: Interpolation) -> Track<T>
+where
+    T: Clone,
+{
+    keys.iter()
+        .map(|(time, value)| Keyframe {
+            time: *time,
+            value: value.clone(),
+            interpolation,
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn assert_close(actual: f32, expected: f32, what: &str) {
+    assert!(
+        (actual - expected).abs() < 1e-2,
+        "{} is {} instead of {}",
+        what,
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn linear_track_hits_keys_and_holds_outside() {
+    let track = track(&[(3.0, 10.0), (1.0, 0.0)], Interpolation::Linear);
+
+    assert_eq!(track.sample(1.0), Some(0.0));
+    assert_eq!(track.sample(3.0), Some(10.0));
+    assert_eq!(track.sample(2.0), Some(5.0));
+    assert_eq!(track.sample(0.0), Some(0.0), "before the first key");
+    assert_eq!(track.sample(5.0), Some(10.0), "after the last key");
+    assert_eq!(Track::<f32>::default().sample(1.0), None);
+}
+
+#[test]
+fn bezier_track_is_smooth_through_keys() {
+    let track = track(&[(0.0, 0.0), (1.0, 1.0), (2.0, 4.0)], Interpolation::Bezier);
+    let slope = |time: f32, step: f32| {
+        (track.sample(time + step).unwrap() - track.sample(time).unwrap()) / step
+    };
+
+    for (time, value) in [(0.0, 0.0), (1.0, 1.0), (2.0, 4.0)] {
+        assert_close(track.sample(time).unwrap(), value, "value at key");
+    }
+    // Middle key has the Catmull-Rom slope on both sides, the ends ease in and out
+    assert_close(slope(1.0, -1e-3), 2.0, "slope arriving at middle key");
+    assert_close(slope(1.0, 1e-3), 2.0, "slope leaving middle key");
+    assert_close(slope(0.0, 1e-3), 0.0, "slope leaving first key");
+    assert_close(slope(2.0, -1e-3), 0.0, "slope arriving at last key");
+    assert_eq!(track.sample(-1.0), Some(0.0));
+    assert_eq!(track.sample(3.0), Some(4.0));
+}
+
+#[test]
+fn material_keys_of_same_kind_blend_parameters() {
+    let track = track(
+        &[
+            (
+                0.0,
+                MaterialDescription::Metal {
+                    albedo: [0.0, 0.2, 0.4],
+                    fuzziness: 0.0,
+                },
+            ),
+            (
+                2.0,
+                MaterialDescription::Metal {
+                    albedo: [1.0, 0.6, 0.4],
+                    fuzziness: 1.0,
+                },
+            ),
+        ],
+        Interpolation::Linear,
+    );
+
+    let MaterialDescription::Metal { albedo, fuzziness } = track.sample(0.5).unwrap() else {
+        panic!("metal keys blend to another kind");
+    };
+    for (actual, expected) in albedo.iter().zip([0.25, 0.3, 0.4]) {
+        assert_close(*actual, expected, "albedo");
+    }
+    assert_close(fuzziness, 0.25, "fuzziness");
+}
+
+#[test]
+fn material_kind_switches_halfway() {
+    let track = track(
+        &[
+            (
+                0.0,
+                MaterialDescription::Lambertian {
+                    albedo: [0.5, 0.5, 0.5],
+                },
+            ),
+            (
+                1.0,
+                MaterialDescription::Dielectric {
+                    refraction_index: 1.5,
+                },
+            ),
+        ],
+        Interpolation::Bezier,
+    );
+
+    assert!(matches!(
+        track.sample(0.4),
+        Some(MaterialDescription::Lambertian {
+            albedo: [0.5, 0.5, 0.5]
+        })
+    ));
+    assert!(matches!(
+        track.sample(0.6),
+        Some(MaterialDescription::Dielectric { refraction_index }) if refraction_index == 1.5
+    ));
+}
+
+#[test]
+fn animated_object_uses_material_at_ray_time() {
+    let sphere = Sphere::new_boxed(
+        Vec3::new([0.0, 0.0, 0.0]),
+        1.0,
+        MaterialType::Lambertian(Lambertian {
+            albedo: Color::new([1.0, 1.0, 1.0]),
+        }),
+    );
+    let animation = ObjectAnimation {
+        material: track(
+            &[
+                (
+                    0.0,
+                    MaterialDescription::Dielectric {
+                        refraction_index: 1.0,
+                    },
+                ),
+                (
+                    1.0,
+                    MaterialDescription::Dielectric {
+                        refraction_index: 2.0,
+                    },
+                ),
+            ],
+            Interpolation::Linear,
+        ),
+        ..Default::default()
+    };
+    let object = Animated::new(sphere, &animation);
+
+    for (time, expected) in [(0.25, 1.25), (0.25, 1.25), (0.75, 1.75)] {
+        let ray = Ray::new(Vec3::new([0.0, 0.0, 5.0]), Vec3::new([0.0, 0.0, -1.0]));
+        let record = object
+            .hit(&ray.with_time(time), 0.0, f32::INFINITY)
+            .unwrap();
+        let MaterialType::Dielectric(material) = record.material else {
+            panic!("hit keeps the object's own material");
+        };
+        assert_close(material.refraction_index, expected, "refraction index");
+    }
+}
+
+/**
+ * Renders frames 0 to 2 of an 8x4 image into a video with extra flags, returning its bytes
+ */
+fn render_video(name: &str, flags: &[&str]) -> Vec<u8> {
+    let directory = std::env::temp_dir().join(format!("renderer-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    let video = directory.join("out.y4m");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_renderer"))
+        .arg("--filename")
+        .arg(directory.join("out.ppm"))
+        .args(["--width", "8", "--height", "4", "--samples-per-pixel", "5"])
+        .args(["--frames", "0..2", "--fps", "30", "--seed", "1"])
+        .args(flags)
+        .arg("--y4m")
+        .arg(&video)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+
+    let bytes = fs::read(&video).unwrap();
+    fs::remove_dir_all(&directory).unwrap();
+    bytes
+}
+
+/**
+ * Video holds the header line and for each frame a FRAME line followed by Y, Cb and Cr planes
+ */
+fn check_video(bytes: &[u8], width: usize, height: usize) {
+    let header = format!("YUV4MPEG2 W{} H{} F30:1 Ip A1:1 C444\n", width, height);
+    assert_eq!(&bytes[..header.len()], header.as_bytes());
+
+    let frame_size = b"FRAME\n".len() + 3 * width * height;
+    assert_eq!(bytes.len(), header.len() + 3 * frame_size);
+    for frame in 0..3 {
+        let start = header.len() + frame * frame_size;
+        assert_eq!(&bytes[start..start + 6], b"FRAME\n");
+    }
+}
+
+#[test]
+fn y4m_video_has_a_full_plane_per_channel_and_frame() {
+    check_video(&render_video("y4m", &[]), 8, 4);
+}
+
+#[test]
+fn y4m_video_is_sized_like_the_rendered_frames() {
+    check_video(
+        &render_video("y4m-crop", &["--crop", "2", "1", "4", "2"]),
+        4,
+        2,
+    );
+    check_video(
+        &render_video(
+            "y4m-full-frame",
+            &["--crop", "2", "1", "4", "2", "--crop-full-frame"],
+        ),
+        8,
+        4,
+    );
+}
+
+#[test]
+fn overshooting_scale_keeps_hits_finite() {
+    let scale = track(
+        &[(0.0, 2.0), (1.0, 0.01), (2.0, 0.001)],
+        Interpolation::Bezier,
+    );
+    assert!(scale.sample(1.5).unwrap() < 0.0, "curve doesn't overshoot");
+
+    let sphere = Sphere::new_boxed(
+        Vec3::new([0.0, 0.0, 0.0]),
+        1.0,
+        MaterialType::Lambertian(Lambertian {
+            albedo: Color::new([1.0, 1.0, 1.0]),
+        }),
+    );
+    let animation = ObjectAnimation {
+        scale,
+        ..Default::default()
+    };
+    let object = Animated::new(sphere, &animation);
+
+    let ray = Ray::new(Vec3::new([0.0, 0.0, 5.0]), Vec3::new([0.0, 0.0, -1.0]));
+    let record = object.hit(&ray.with_time(1.5), 0.0, f32::INFINITY).unwrap();
+    assert!(record.t.is_finite() && record.point.e.iter().all(|value| value.is_finite()));
+}
+
+#[test]
+fn scene_files_with_non_positive_scale_are_rejected() {
+    let path = std::env::temp_dir().join(format!("renderer-scale-{}.json", std::process::id()));
+    fs::write(
+        &path,
+        r#"{ "animation": { "objects": [{ "object": 1, "scale": [
+            { "time": 0.0, "value": 1.0 },
+            { "time": 1.0, "value": 0.0 }
+        ] }] } }"#,
+    )
+    .unwrap();
+    let scene_file = SceneFile::load(&path);
+    fs::remove_file(&path).unwrap();
+
+    let err = scene_file.unwrap_err();
+    assert!(
+        err.contains("scale of object 1 must be positive"),
+        "{}",
+        err
+    );
+}
@@ -0,0 +1,284 @@
+use std::f64::consts::PI;
+
+use renderer::{
+    materials::{
+        material::{Material, MaterialType},
+        scatter_record::ScatterRecord,
+    },
+    math::{random::reseed, vec3::Vec3},
+    shapes::hit_record::HitRecord,
+    util::{point::Point, ray::Ray},
+};
+
+/**
+ * Bins over cosine of polar angle and azimuth, equal solid angle each
+ */
+const COS_BINS: usize = 20;
+const PHI_BINS: usize = 40;
+const COS_STEP: f64 = 2.0 / COS_BINS as f64;
+const PHI_STEP: f64 = 2.0 * PI / PHI_BINS as f64;
+/// Midpoint rule samples per bin side when integrating the pdf, doubled up to the maximum
+const INTEGRATION_STEPS: usize = 8;
+const MAX_INTEGRATION_STEPS: usize = 256;
+/// Pooled bins need at least this many expected samples for the chi-square approximation
+const MIN_EXPECTED: f64 = 5.0;
+
+/**
+ * Surface point at origin with normal +z and tangent +x. Shapes flip the normal of back face
+ * hits so it always points to the incident side, front_face only tells which medium the ray
+ * arrives from.
+ */
+pub fn hit(material: &MaterialType, front_face: bool) -> HitRecord {
+    HitRecord {
+        point: Point::default(),
+        normal: Vec3::new([0.0, 0.0, 1.0]),
+        tangent: Vec3::new([1.0, 0.0, 0.0]),
+        t: 1.0,
+        u: 0.5,
+        v: 0.5,
+        front_face,
+        material: material.clone(),
+        object_id: 0,
+    }
+}
+
+/**
+ * Ray arriving at the origin from direction wo
+ */
+pub fn ray_from(wo: &Vec3) -> Ray {
+    Ray::new(*wo, -*wo)
+}
+
+/**
+ * Unit direction at polar angle theta from normal (+z), in xz plane
+ */
+pub fn direction(theta_degrees: f32) -> Vec3 {
+    let theta = theta_degrees.to_radians();
+    Vec3::new([theta.sin(), 0.0, theta.cos()])
+}
+
+/**
+ * Mirrors direction through the surface, from the frame of one side to the other
+ */
+pub fn mirror(direction: &Vec3) -> Vec3 {
+    Vec3::new([direction.x(), direction.y(), -direction.z()])
+}
+
+/**
+ * Scatters seeded samples of ray hitting the surface
+ */
+pub fn samples(
+    material: &MaterialType,
+    hit: &HitRecord,
+    wo: &Vec3,
+    count: usize,
+    seed: u64,
+) -> Vec<Option<ScatterRecord>> {
+    reseed(seed);
+    let ray = ray_from(wo);
+    (0..count).map(|_| material.scatter(&ray, hit)).collect()
+}
+
+fn bin(direction: &Vec3) -> usize {
+    let direction = direction.unit_vector();
+    let cos_bin =
+        (((direction.z() as f64 + 1.0) / 2.0 * COS_BINS as f64) as usize).min(COS_BINS - 1);
+    let phi = (direction.y() as f64).atan2(direction.x() as f64) + PI;
+    let phi_bin = ((phi / (2.0 * PI) * PHI_BINS as f64) as usize).min(PHI_BINS - 1);
+    cos_bin * PHI_BINS + phi_bin
+}
+
+/**
+ * Pdf integrated over every bin of the sphere of directions
+ */
+fn integrate_pdf(pdf: impl Fn(&Vec3) -> f32) -> Vec<f64> {
+    (0..COS_BINS * PHI_BINS)
+        .map(|bin| {
+            let (cos_bin, phi_bin) = ((bin / PHI_BINS) as f64, (bin % PHI_BINS) as f64);
+            let cos_range = (-1.0 + cos_bin * COS_STEP, -1.0 + (cos_bin + 1.0) * COS_STEP);
+            let phi_range = (-PI + phi_bin * PHI_STEP, -PI + (phi_bin + 1.0) * PHI_STEP);
+
+            // Sharp lobes need finer steps, refine until the estimate settles
+            let mut steps = INTEGRATION_STEPS;
+            let mut integral = integrate_bin(&pdf, cos_range, phi_range, steps);
+            while steps < MAX_INTEGRATION_STEPS {
+                steps *= 2;
+                let refined = integrate_bin(&pdf, cos_range, phi_range, steps);
+                let converged = (refined - integral).abs() <= 1e-3 * refined + 1e-9;
+                integral = refined;
+                if converged {
+                    break;
+                }
+            }
+            integral
+        })
+        .collect()
+}
+
+/**
+ * Midpoint rule over a rectangle of cosine and azimuth
+ */
+fn integrate_bin(
+    pdf: &impl Fn(&Vec3) -> f32,
+    cos_range: (f64, f64),
+    phi_range: (f64, f64),
+    steps: usize,
+) -> f64 {
+    let cos_step = (cos_range.1 - cos_range.0) / steps as f64;
+    let phi_step = (phi_range.1 - phi_range.0) / steps as f64;
+
+    let mut integral = 0.0;
+    for i in 0..steps {
+        let cos = cos_range.0 + (i as f64 + 0.5) * cos_step;
+        let sin = (1.0 - cos * cos).max(0.0).sqrt();
+        for j in 0..steps {
+            let phi = phi_range.0 + (j as f64 + 0.5) * phi_step;
+            let direction = Vec3::new([
+                (sin * phi.cos()) as f32,
+                (sin * phi.sin()) as f32,
+                cos as f32,
+            ]);
+            integral += pdf(&direction) as f64;
+        }
+    }
+    integral * cos_step * phi_step
+}
+
+/**
+ * Result of comparing sampled directions against the density reported by Material::pdf
+ */
+pub struct GoodnessOfFit {
+    pub chi_square: f64,
+    pub degrees_of_freedom: usize,
+    pub p_value: f64,
+    /// Fraction of samples scatter rejected
+    pub absorbed: f64,
+    /// Probability of rejection implied by the pdf not integrating to one
+    pub expected_absorbed: f64,
+}
+
+/**
+ * Chi-square goodness of fit test of scatter's direction distribution against pdf. Bins with
+ * few expected samples are pooled into one.
+ */
+pub fn chi_square_test(
+    material: &MaterialType,
+    hit: &HitRecord,
+    wo: &Vec3,
+    count: usize,
+    seed: u64,
+) -> GoodnessOfFit {
+    let ray = ray_from(wo);
+    let expected: Vec<f64> = integrate_pdf(|direction| material.pdf(&ray, hit, direction))
+        .iter()
+        .map(|probability| probability * count as f64)
+        .collect();
+
+    let mut observed = vec![0.0; expected.len()];
+    let mut absorbed = 0;
+    for sample in samples(material, hit, wo, count, seed) {
+        match sample {
+            Some(sample) => observed[bin(&sample.scattered_ray.direction())] += 1.0,
+            None => absorbed += 1,
+        }
+    }
+
+    let mut chi_square = 0.0;
+    let mut bins = 0;
+    let (mut pooled_observed, mut pooled_expected) = (0.0, 0.0);
+    for (observed, expected) in observed.iter().zip(&expected) {
+        if *expected < MIN_EXPECTED {
+            pooled_observed += observed;
+            pooled_expected += expected;
+        } else {
+            chi_square += (observed - expected).powi(2) / expected;
+            bins += 1;
+        }
+    }
+    if pooled_expected > 0.0 || pooled_observed > 0.0 {
+        // Samples where the pdf claims almost none are only tolerated in small numbers
+        chi_square +=
+            (pooled_observed - pooled_expected).powi(2) / pooled_expected.max(MIN_EXPECTED);
+        bins += 1;
+    }
+
+    let degrees_of_freedom = bins.max(2) - 1;
+    GoodnessOfFit {
+        chi_square,
+        degrees_of_freedom,
+        p_value: chi_square_survival(chi_square, degrees_of_freedom as f64),
+        absorbed: absorbed as f64 / count as f64,
+        expected_absorbed: 1.0 - expected.iter().sum::<f64>() / count as f64,
+    }
+}
+
+/**
+ * Probability of a chi-square statistic at least this large
+ */
+pub fn chi_square_survival(chi_square: f64, degrees_of_freedom: f64) -> f64 {
+    1.0 - regularized_gamma(degrees_of_freedom / 2.0, chi_square / 2.0)
+}
+
+/**
+ * Regularized lower incomplete gamma function P(a, x), series expansion below a + 1 and
+ * continued fraction above (Numerical Recipes 6.2)
+ */
+fn regularized_gamma(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    let log_prefix = a * x.ln() - x - ln_gamma(a);
+
+    if x < a + 1.0 {
+        let (mut term, mut sum, mut n) = (1.0 / a, 1.0 / a, a);
+        while term.abs() > sum.abs() * 1e-15 {
+            n += 1.0;
+            term *= x / n;
+            sum += term;
+        }
+        return sum * log_prefix.exp();
+    }
+
+    let tiny = 1e-300;
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / tiny;
+    let mut d = 1.0 / b;
+    let mut h = d;
+    for i in 1..1000 {
+        let an = -(i as f64) * (i as f64 - a);
+        b += 2.0;
+        d = an * d + b;
+        d = if d.abs() < tiny { tiny } else { d };
+        c = b + an / c;
+        c = if c.abs() < tiny { tiny } else { c };
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < 1e-15 {
+            break;
+        }
+    }
+    1.0 - log_prefix.exp() * h
+}
+
+/**
+ * Lanczos approximation of ln Γ(x) for x > 0
+ */
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.120_865_097_386_617_9e-2,
+        -0.539_523_938_495_3e-5,
+    ];
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let series = COEFFICIENTS
+        .iter()
+        .enumerate()
+        .fold(1.000_000_000_190_015, |sum, (i, c)| {
+            sum + c / (x + 1.0 + i as f64)
+        });
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
@@ -0,0 +1,527 @@
+mod bsdf;
+
+use bsdf::{chi_square_test, direction, hit, mirror, ray_from, samples};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use renderer::{
+    materials::{
+        conductor::Conductor,
+        dielectric::Dielectric,
+        interactions::{fresnel_dielectric, reflect},
+        lambertian::Lambertian,
+        material::{Material, MaterialType},
+        metal::Metal,
+        normal_mapped::{NormalMapped, SurfaceMap},
+        principled::Principled,
+        rough_dielectric::RoughDielectric,
+    },
+    math::vec3::Vec3,
+    shapes::{traits::Hit, triangle::Triangle},
+    textures::texture::TextureType,
+    util::{color::Color, point::Point},
+};
+
+const SEED: u64 = 41;
+const SAMPLES: usize = 100_000;
+/// Chance of a false failure over the whole suite is below 1% with the Šidák correction
+const SIGNIFICANCE: f64 = 0.01;
+
+fn white() -> Color {
+    Color::new([1.0, 1.0, 1.0])
+}
+
+/**
+ * Materials with a sampled lobe, scatter must follow their pdf
+ */
+fn sampled_materials() -> Vec<(&'static str, MaterialType)> {
+    vec![
+        (
+            "lambertian",
+            MaterialType::Lambertian(Lambertian { albedo: white() }),
+        ),
+        ("conductor", MaterialType::Conductor(Conductor::gold(0.5))),
+        (
+            "rough_dielectric",
+            MaterialType::RoughDielectric(RoughDielectric {
+                refraction_index: 1.5,
+                roughness: 0.5,
+            }),
+        ),
+        (
+            "principled",
+            MaterialType::Principled(Box::new(Principled {
+                base_color: TextureType::constant(1.0),
+                ..Default::default()
+            })),
+        ),
+        (
+            "principled_metallic_anisotropic",
+            MaterialType::Principled(Box::new(Principled {
+                base_color: TextureType::constant(1.0),
+                metallic: TextureType::constant(1.0),
+                anisotropy: TextureType::constant(0.6),
+                ..Default::default()
+            })),
+        ),
+        (
+            "principled_clearcoat_sheen",
+            MaterialType::Principled(Box::new(Principled {
+                base_color: TextureType::constant(1.0),
+                clearcoat: TextureType::constant(1.0),
+                sheen: TextureType::constant(1.0),
+                ..Default::default()
+            })),
+        ),
+        (
+            "principled_transmission",
+            MaterialType::Principled(Box::new(Principled {
+                base_color: TextureType::constant(1.0),
+                transmission: TextureType::constant(0.5),
+                ..Default::default()
+            })),
+        ),
+        (
+            "normal_mapped",
+            MaterialType::NormalMapped(Box::new(NormalMapped {
+                material: MaterialType::Lambertian(Lambertian { albedo: white() }),
+                map: SurfaceMap::Normal {
+                    texture: TextureType::solid(Color::new([0.6, 0.5, 1.0])),
+                    strength: 1.0,
+                },
+            })),
+        ),
+    ]
+}
+
+/**
+ * Both sides of the surface and a grazing angle
+ */
+fn configurations() -> Vec<(f32, bool)> {
+    vec![(30.0, true), (75.0, true), (40.0, false)]
+}
+
+#[test]
+fn sampled_directions_follow_pdf() {
+    let materials = sampled_materials();
+    let tests = materials.len() * configurations().len();
+    let threshold = 1.0 - (1.0 - SIGNIFICANCE).powf(1.0 / tests as f64);
+
+    let mut failures = vec![];
+    for (name, material) in &materials {
+        for (index, (theta, front_face)) in configurations().into_iter().enumerate() {
+            let hit = hit(material, front_face);
+            let fit = chi_square_test(
+                material,
+                &hit,
+                &direction(theta),
+                SAMPLES,
+                SEED + index as u64,
+            );
+
+            if fit.p_value < threshold {
+                failures.push(format!(
+                    "{} at {}° ({} face): chi-square {:.1} with {} degrees of freedom, p = {:.2e}",
+                    name,
+                    theta,
+                    if front_face { "front" } else { "back" },
+                    fit.chi_square,
+                    fit.degrees_of_freedom,
+                    fit.p_value
+                ));
+            }
+            if (fit.absorbed - fit.expected_absorbed).abs() > 0.01 {
+                failures.push(format!(
+                    "{} at {}°: {:.3} of samples rejected, pdf implies {:.3}",
+                    name, theta, fit.absorbed, fit.expected_absorbed
+                ));
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn sample_weight_equals_eval_over_pdf() {
+    for (name, material) in sampled_materials() {
+        for (theta, front_face) in configurations() {
+            let hit = hit(&material, front_face);
+            let wo = direction(theta);
+            let ray = ray_from(&wo);
+
+            for sample in samples(&material, &hit, &wo, 1000, SEED)
+                .into_iter()
+                .flatten()
+            {
+                let wi = sample.scattered_ray.direction();
+                let pdf = material.pdf(&ray, &hit, &wi);
+                let expected = material.eval(&ray, &hit, &wi) / pdf;
+
+                let sampled_pdf = sample.pdf.unwrap();
+                assert!(
+                    (sampled_pdf - pdf).abs() <= 1e-3 * pdf,
+                    "{} reports pdf {} when sampling, {} when evaluating",
+                    name,
+                    sampled_pdf,
+                    pdf
+                );
+                assert!(
+                    (sample.attenuation - expected).length() <= 1e-3 * expected.length().max(1.0),
+                    "{} at {}°: weight {:?} differs from eval / pdf {:?}",
+                    name,
+                    theta,
+                    sample.attenuation.e,
+                    expected.e
+                );
+            }
+        }
+    }
+}
+
+/**
+ * Mean sample weight is the fraction of light the surface scatters, it can't exceed one
+ * for white materials and equals one for lossless ones
+ */
+#[test]
+fn white_furnace() {
+    let lossy = vec![
+        (
+            "metal",
+            MaterialType::Metal(Metal {
+                albedo: white(),
+                fuzziness: 0.5,
+            }),
+        ),
+        (
+            "conductor",
+            MaterialType::Conductor(Conductor {
+                eta: Color::new([0.0, 0.0, 0.0]),
+                k: Color::new([1e3, 1e3, 1e3]),
+                roughness: 0.8,
+            }),
+        ),
+    ];
+    let lossless = vec![
+        (
+            "dielectric",
+            MaterialType::Dielectric(Dielectric {
+                refraction_index: 1.5,
+                dispersion: None,
+            }),
+        ),
+        (
+            "smooth_rough_dielectric",
+            MaterialType::RoughDielectric(RoughDielectric {
+                refraction_index: 1.5,
+                roughness: 0.0,
+            }),
+        ),
+    ];
+
+    let all = sampled_materials()
+        .into_iter()
+        .map(|material| (material, false))
+        .chain(lossy.into_iter().map(|material| (material, false)))
+        .chain(lossless.into_iter().map(|material| (material, true)));
+
+    for ((name, material), conserves_energy) in all {
+        let conserves_energy = conserves_energy || name == "lambertian" || name == "normal_mapped";
+        for (theta, front_face) in configurations() {
+            let hit = hit(&material, front_face);
+            let weights: Vec<f32> = samples(&material, &hit, &direction(theta), SAMPLES, SEED)
+                .iter()
+                .map(|sample| {
+                    sample
+                        .as_ref()
+                        .map_or(0.0, |s| s.attenuation.e.iter().sum::<f32>() / 3.0)
+                })
+                .collect();
+            let mean = weights.iter().sum::<f32>() / weights.len() as f32;
+            let variance =
+                weights.iter().map(|w| (w - mean).powi(2)).sum::<f32>() / weights.len() as f32;
+            let tolerance = 4.0 * (variance / weights.len() as f32).sqrt() + 1e-3;
+
+            assert!(
+                mean <= 1.0 + tolerance,
+                "{} at {}° reflects {:.4} of the light",
+                name,
+                theta,
+                mean
+            );
+            if conserves_energy {
+                assert!(
+                    (mean - 1.0).abs() <= tolerance,
+                    "{} at {}° should keep all light, keeps {:.4}",
+                    name,
+                    theta,
+                    mean
+                );
+            }
+        }
+    }
+}
+
+/**
+ * BSDFs without refraction are symmetric, f(wo, wi) = f(wi, wo). Material::eval includes the
+ * cosine of wi, which is divided out. Tilted shading normals aren't reciprocal by design, the
+ * normal mapped material is checked with a flat map.
+ */
+#[test]
+fn reflection_is_reciprocal() {
+    let flat = MaterialType::NormalMapped(Box::new(NormalMapped {
+        material: MaterialType::Lambertian(Lambertian { albedo: white() }),
+        map: SurfaceMap::Normal {
+            texture: TextureType::solid(Color::new([0.5, 0.5, 1.0])),
+            strength: 1.0,
+        },
+    }));
+    let materials = sampled_materials()
+        .into_iter()
+        .filter(|(name, _)| *name != "normal_mapped")
+        .chain([("flat_normal_mapped", flat)]);
+
+    let mut rng = StdRng::seed_from_u64(SEED);
+    for (name, material) in materials {
+        let hit = hit(&material, true);
+        for _ in 0..1000 {
+            let wo = random_upper_direction(&mut rng);
+            let wi = random_upper_direction(&mut rng);
+
+            let forward = material.eval(&ray_from(&wo), &hit, &wi) / wi.z();
+            let backward = material.eval(&ray_from(&wi), &hit, &wo) / wo.z();
+            assert!(
+                (forward - backward).length() <= 1e-3 * forward.length().max(1e-2),
+                "{}: f(wo, wi) = {:?} but f(wi, wo) = {:?} for wo {:?}, wi {:?}",
+                name,
+                forward.e,
+                backward.e,
+                wo.e,
+                wi.e
+            );
+        }
+    }
+}
+
+/**
+ * A back face is the front seen from behind, so its shading normal is the front one turned
+ * around. Hits come from a triangle with u along x and v along y to go through the flipping
+ * done by the shape.
+ */
+#[test]
+fn normal_map_is_mirrored_on_back_faces() {
+    let material = MaterialType::NormalMapped(Box::new(NormalMapped {
+        material: MaterialType::Lambertian(Lambertian { albedo: white() }),
+        map: SurfaceMap::Normal {
+            texture: TextureType::solid(Color::new([0.7, 0.6, 1.0])),
+            strength: 1.0,
+        },
+    }));
+    let triangle = Triangle::new(
+        [
+            Point::new([-1.0, -1.0, 0.0]),
+            Point::new([1.0, -1.0, 0.0]),
+            Point::new([0.0, 1.0, 0.0]),
+        ],
+        [[0.0, 0.0], [1.0, 0.0], [0.5, 1.0]],
+        material.clone(),
+    );
+
+    let mut rng = StdRng::seed_from_u64(SEED);
+    for _ in 0..1000 {
+        let wo = random_upper_direction(&mut rng);
+        let wi = random_upper_direction(&mut rng);
+        let front = triangle.hit(&ray_from(&wo), 0.001, f32::INFINITY).unwrap();
+        let back = triangle.hit(&ray_from(&-wo), 0.001, f32::INFINITY).unwrap();
+        assert!(front.front_face && !back.front_face);
+
+        let front_value = material.eval(&ray_from(&wo), &front, &wi);
+        let back_value = material.eval(&ray_from(&-wo), &back, &-wi);
+        assert!(
+            (front_value - back_value).length() <= 1e-5,
+            "front {:?} but back {:?} for wo {:?}, wi {:?}",
+            front_value.e,
+            back_value.e,
+            wo.e,
+            wi.e
+        );
+    }
+}
+
+/**
+ * Refraction doesn't scale radiance by the squared index ratio, like the smooth dielectric,
+ * so the transmission BSDF satisfies f(wo, wi) / η(wi)² = f(wi, wo) / η(wo)² where η is the
+ * index on the side of the direction
+ */
+#[test]
+fn transmission_is_reciprocal() {
+    let refraction_index = 1.5;
+    let material = MaterialType::RoughDielectric(RoughDielectric {
+        refraction_index,
+        roughness: 0.5,
+    });
+    let (outside, inside) = (hit(&material, true), hit(&material, false));
+
+    let mut rng = StdRng::seed_from_u64(SEED);
+    for _ in 0..1000 {
+        let wo = random_upper_direction(&mut rng);
+        let wi = -random_upper_direction(&mut rng);
+
+        let forward = material.eval(&ray_from(&wo), &outside, &wi).x() / wi.z().abs();
+        // Viewed from inside the normal flips, so both directions are mirrored into its frame
+        let backward = material
+            .eval(&ray_from(&mirror(&wi)), &inside, &mirror(&wo))
+            .x()
+            / wo.z().abs();
+        let (forward, backward) = (forward / refraction_index.powi(2), backward);
+        assert!(
+            (forward - backward).abs() <= 1e-3 * forward.abs().max(1e-2),
+            "f(wo, wi) / η² = {} but f(wi, wo) = {} for wo {:?}, wi {:?}",
+            forward,
+            backward,
+            wo.e,
+            wi.e
+        );
+    }
+}
+
+#[test]
+fn reflect_mirrors_about_normal() {
+    let normal = Vec3::new([0.0, 0.0, 1.0]);
+    let reflected = reflect(Vec3::new([0.6, 0.0, -0.8]), &normal);
+    assert!((reflected - Vec3::new([0.6, 0.0, 0.8])).length() < 1e-6);
+
+    let tilted = Vec3::new([1.0, 2.0, 2.0]).unit_vector();
+    let incoming = Vec3::new([0.3, -0.5, -0.7]);
+    let reflected = reflect(incoming, &tilted);
+    assert!((reflected.length() - incoming.length()).abs() < 1e-6);
+    assert!((reflected.dot(&tilted) + incoming.dot(&tilted)).abs() < 1e-6);
+}
+
+/**
+ * Specular materials scatter into the mirror direction, or the refracted direction for
+ * dielectrics, and following the scattered ray backwards returns the incoming one
+ */
+#[test]
+fn specular_directions_are_reversible() {
+    let materials = vec![
+        (
+            "metal",
+            MaterialType::Metal(Metal {
+                albedo: white(),
+                fuzziness: 0.0,
+            }),
+        ),
+        (
+            "smooth_conductor",
+            MaterialType::Conductor(Conductor::gold(0.0)),
+        ),
+        (
+            "dielectric",
+            MaterialType::Dielectric(Dielectric {
+                refraction_index: 1.5,
+                dispersion: None,
+            }),
+        ),
+        (
+            "smooth_rough_dielectric",
+            MaterialType::RoughDielectric(RoughDielectric {
+                refraction_index: 1.5,
+                roughness: 0.0,
+            }),
+        ),
+    ];
+
+    for (name, material) in materials {
+        let wo = direction(35.0);
+        for sample in samples(&material, &hit(&material, true), &wo, 100, SEED) {
+            let wi = sample.unwrap().scattered_ray.direction().unit_vector();
+            let reflected = wi.z() > 0.0;
+            let (back_hit, back_wo) = match reflected {
+                true => (hit(&material, true), wi),
+                false => (hit(&material, false), mirror(&wi)),
+            };
+
+            let returned = samples(&material, &back_hit, &back_wo, 100, SEED)
+                .into_iter()
+                .flatten()
+                .map(|sample| sample.scattered_ray.direction().unit_vector())
+                .map(|direction| match reflected {
+                    true => direction,
+                    false => mirror(&direction),
+                })
+                .any(|direction| (direction - wo).length() < 1e-4);
+            assert!(
+                returned,
+                "{}: ray scattered to {:?} never scatters back to {:?}",
+                name, wi.e, wo.e
+            );
+
+            if reflected {
+                assert!(
+                    (wi - Vec3::new([-wo.x(), -wo.y(), wo.z()])).length() < 1e-4,
+                    "{}: reflection {:?} is not the mirror direction",
+                    name,
+                    wi.e
+                );
+            }
+        }
+    }
+}
+
+/**
+ * Smooth dielectrics choose between reflection and refraction with probability of the
+ * Fresnel reflectance. The smooth dielectric uses Schlick's approximation.
+ */
+#[test]
+fn dielectric_reflects_with_fresnel_probability() {
+    let refraction_index = 1.5_f32;
+    let schlick = |cos: f32, ratio: f32| {
+        let r0 = ((1.0 - ratio) / (1.0 + ratio)).powi(2);
+        r0 + (1.0 - r0) * (1.0 - cos).powi(5)
+    };
+
+    let cases = vec![
+        (
+            MaterialType::Dielectric(Dielectric {
+                refraction_index,
+                dispersion: None,
+            }),
+            Box::new(move |cos: f32| schlick(cos, 1.0 / refraction_index))
+                as Box<dyn Fn(f32) -> f32>,
+        ),
+        (
+            MaterialType::RoughDielectric(RoughDielectric {
+                refraction_index,
+                roughness: 0.0,
+            }),
+            Box::new(move |cos: f32| fresnel_dielectric(cos, refraction_index)),
+        ),
+    ];
+
+    for (material, reflectance) in cases {
+        for theta in [0.0_f32, 45.0, 80.0] {
+            let wo = direction(theta);
+            let reflected = samples(&material, &hit(&material, true), &wo, SAMPLES, SEED)
+                .iter()
+                .filter(|sample| sample.as_ref().unwrap().scattered_ray.direction().z() > 0.0)
+                .count();
+
+            let expected = reflectance(wo.z()) as f64;
+            let observed = reflected as f64 / SAMPLES as f64;
+            let deviation = (expected * (1.0 - expected) / SAMPLES as f64).sqrt();
+            assert!(
+                (observed - expected).abs() <= 5.0 * deviation + 1e-4,
+                "reflected {:.4} of rays at {}°, Fresnel reflectance is {:.4}",
+                observed,
+                theta,
+                expected
+            );
+        }
+    }
+}
+
+fn random_upper_direction(rng: &mut StdRng) -> Vec3 {
+    let cos: f32 = rng.gen_range(0.05..1.0);
+    let phi: f32 = rng.gen_range(0.0..std::f32::consts::TAU);
+    let sin = (1.0 - cos * cos).sqrt();
+    Vec3::new([sin * phi.cos(), sin * phi.sin(), cos])
+}
@@ -0,0 +1,267 @@
+use std::{f32::consts::PI, fs, process::Command};
+
+use renderer::{
+    math::{random::reseed, vec3::Vec3},
+    scene::{
+        camera::{stereo::StereoLayout, CameraModel, CameraType},
+        camera_description::{ApertureDescription, CameraDescription, Projection},
+    },
+    shapes::hit_collection::HitCollection,
+    util::ray::Ray,
+    FilmSettings, Render, RenderError, RenderSettings, Renderer, Scene,
+};
+
+const ASPECT_RATIO: f32 = 2.0;
+
+fn stereo(convergence: Option<f32>) -> CameraType {
+    CameraDescription {
+        look_from: [0.0, 0.0, 0.0],
+        look_at: [0.0, 0.0, -1.0],
+        aperture: 0.0,
+        projection: Projection::Stereo {
+            ipd: 0.064,
+            layout: StereoLayout::SideBySide,
+            convergence,
+        },
+        ..Default::default()
+    }
+    .build(ASPECT_RATIO)
+    .unwrap()
+}
+
+/**
+ * Rays through the same position of the left and right eye view
+ */
+fn eye_rays(camera: &CameraType, u: f32, v: f32) -> (Ray, Ray) {
+    (
+        camera.get_ray(u / 2.0, v).unwrap(),
+        camera.get_ray(0.5 + u / 2.0, v).unwrap(),
+    )
+}
+
+fn is_finite(vector: &Vec3) -> bool {
+    vector.e.iter().all(|value| value.is_finite())
+}
+
+#[test]
+fn stereo_without_convergence_looks_parallel() {
+    let camera = stereo(None);
+    for (u, v) in [(0.5, 0.5), (0.1, 0.8), (0.9, 0.2)] {
+        let (left, right) = eye_rays(&camera, u, v);
+        assert!(is_finite(&left.origin()) && is_finite(&left.direction()));
+        assert!(is_finite(&right.origin()) && is_finite(&right.direction()));
+
+        let parallel = left
+            .direction()
+            .unit_vector()
+            .dot(&right.direction().unit_vector());
+        assert!(parallel > 1.0 - 1e-6, "eyes diverge at ({}, {})", u, v);
+        let offset = right.origin() - left.origin();
+        assert!((offset.length() - 0.064).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn stereo_eyes_meet_at_convergence() {
+    let camera = stereo(Some(2.0));
+    for (u, v) in [(0.5, 0.5), (0.1, 0.8), (0.9, 0.2)] {
+        let (left, right) = eye_rays(&camera, u, v);
+        // Points where both rays cross the plane at convergence distance
+        let at_plane = |ray: &Ray| ray.at(-2.0 / ray.direction().z());
+        let distance = (at_plane(&left) - at_plane(&right)).length();
+        assert!(
+            distance < 1e-5,
+            "eyes miss by {} at ({}, {})",
+            distance,
+            u,
+            v
+        );
+    }
+}
+
+fn physical(f_stop: f32, shutter_s: f32, iso: f32, aperture: ApertureDescription) -> CameraType {
+    CameraDescription {
+        look_from: [0.0, 0.0, 0.0],
+        look_at: [0.0, 0.0, -1.0],
+        projection: Projection::Physical {
+            focal_length_mm: 50.0,
+            sensor_mm: [36.0, 24.0],
+            f_stop,
+            shutter_s,
+            iso,
+            aperture,
+            cat_eye: 0.0,
+        },
+        ..Default::default()
+    }
+    .build(ASPECT_RATIO)
+    .unwrap()
+}
+
+#[test]
+fn physical_exposure_follows_stops() {
+    let exposure = |f_stop, shutter_s, iso| {
+        physical(f_stop, shutter_s, iso, ApertureDescription::Circle).exposure()
+    };
+    let reference = exposure(2.8, 1.0 / 125.0, 100.0);
+    // Reference settings are about EV 10, which maps scene radiance 1 to 1
+    assert!(
+        (reference - 1.0).abs() < 0.05,
+        "reference exposure {}",
+        reference
+    );
+
+    // Each change is one stop
+    let stops = [
+        (exposure(2.0, 1.0 / 125.0, 100.0), 2.0),
+        (exposure(4.0, 1.0 / 125.0, 100.0), 0.5),
+        (exposure(2.8, 1.0 / 250.0, 100.0), 0.5),
+        (exposure(2.8, 1.0 / 60.0, 100.0), 2.0),
+        (exposure(2.8, 1.0 / 125.0, 200.0), 2.0),
+        (exposure(2.8, 1.0 / 125.0, 50.0), 0.5),
+    ];
+    for (exposure, expected) in stops {
+        let ratio = exposure / reference;
+        assert!(
+            (ratio / expected - 1.0).abs() < 0.05,
+            "exposure changed by {} instead of {}",
+            ratio,
+            expected
+        );
+    }
+}
+
+/**
+ * Lens positions of the rays form the aperture polygon, which is the bokeh shape
+ */
+#[test]
+fn polygon_aperture_shapes_bokeh() {
+    let (blades, rotation) = (6, 15.0_f32);
+    let camera = physical(
+        2.8,
+        1.0 / 125.0,
+        100.0,
+        ApertureDescription::Polygon { blades, rotation },
+    );
+    let lens_radius = 50.0 / 2.8 / 1000.0 / 2.0;
+    let step = 2.0 * PI / blades as f32;
+    let apothem = (step / 2.0).cos();
+
+    reseed(3);
+    let mut outside_inscribed_circle = 0;
+    for _ in 0..10_000 {
+        // Camera looks along -z with up +y, lens u and v are world x and y
+        let origin = camera.get_ray(0.5, 0.5).unwrap().origin() / lens_radius;
+        for edge in 0..blades {
+            let angle = rotation.to_radians() + step * (edge as f32 + 0.5);
+            let distance = origin.x() * angle.cos() + origin.y() * angle.sin();
+            assert!(
+                distance <= apothem + 1e-4,
+                "{:?} outside edge {}",
+                origin.e,
+                edge
+            );
+        }
+        outside_inscribed_circle += (origin.length() > apothem + 0.02) as usize;
+    }
+    assert!(outside_inscribed_circle > 100, "samples fill a circle only");
+}
+
+#[test]
+fn physical_settings_must_be_positive() {
+    for (f_stop, shutter_s, iso) in [
+        (0.0, 1.0 / 125.0, 100.0),
+        (2.8, -1.0, 100.0),
+        (2.8, 1.0 / 125.0, f32::NAN),
+    ] {
+        let camera = CameraDescription {
+            projection: Projection::Physical {
+                focal_length_mm: 50.0,
+                sensor_mm: [36.0, 24.0],
+                f_stop,
+                shutter_s,
+                iso,
+                aperture: ApertureDescription::Circle,
+                cat_eye: 0.0,
+            },
+            ..Default::default()
+        };
+        assert!(matches!(
+            camera.build(ASPECT_RATIO),
+            Err(RenderError::InvalidSettings(_))
+        ));
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_renderer"))
+        .args(["--f-stop", "0", "--width", "8", "--height", "4"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("f-stop must be positive"));
+}
+
+#[test]
+fn missing_aperture_mask_is_an_error() {
+    let camera = CameraDescription {
+        projection: Projection::Physical {
+            focal_length_mm: 50.0,
+            sensor_mm: [36.0, 24.0],
+            f_stop: 2.8,
+            shutter_s: 1.0 / 125.0,
+            iso: 100.0,
+            aperture: ApertureDescription::Mask {
+                path: "missing_mask.ppm".to_string(),
+            },
+            cat_eye: 0.0,
+        },
+        ..Default::default()
+    };
+
+    assert!(matches!(
+        camera.build(ASPECT_RATIO),
+        Err(RenderError::Io(_))
+    ));
+}
+
+#[test]
+fn scene_without_cameras_renders_through_default_camera() {
+    let scene = Scene {
+        cameras: vec![],
+        world: HitCollection::default(),
+    };
+    let settings = RenderSettings {
+        width: 8,
+        height: 4,
+        samples_per_pixel: 1,
+        ..Default::default()
+    };
+
+    let frame = Renderer::new(scene, FilmSettings::default())
+        .render(&settings)
+        .unwrap();
+    assert_eq!((frame.width, frame.height), (8, 4));
+}
+
+#[test]
+fn scene_file_without_cameras_renders_through_default_camera() {
+    let directory =
+        std::env::temp_dir().join(format!("renderer-no-cameras-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    let scene_file = directory.join("scene.json");
+    fs::write(&scene_file, r#"{ "animation": { "objects": [] } }"#).unwrap();
+    let image = directory.join("out.ppm");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_renderer"))
+        .arg("--scene")
+        .arg(&scene_file)
+        .arg("--filename")
+        .arg(&image)
+        .args(["--width", "8", "--height", "4", "--samples-per-pixel", "5"])
+        .output()
+        .unwrap();
+    let rendered = image.exists();
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert!(output.status.success(), "{:?}", output);
+    assert!(rendered);
+}
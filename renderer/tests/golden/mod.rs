@@ -0,0 +1,379 @@
+use std::{
+    env, fs,
+    io::{self, BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use shared::data::Frame;
+
+/**
+ * Setting this variable to 1 rewrites references from current renders instead of comparing
+ */
+pub const UPDATE_VARIABLE: &str = "UPDATE_GOLDEN";
+
+/**
+ * Largest differences a render may have from its reference. Seeded renders are identical on
+ * the machine the reference was made on, tolerances absorb floating point differences of
+ * other platforms and compilers.
+ */
+pub struct Tolerance {
+    /// Root mean square error of 8-bit channels scaled to [0, 1]
+    pub max_rmse: f32,
+    pub min_psnr: f32,
+    /// Mean of the per pixel perceptual error
+    pub max_flip: f32,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            max_rmse: 0.01,
+            min_psnr: 40.0,
+            max_flip: 0.01,
+        }
+    }
+}
+
+/**
+ * 8-bit RGB image as written to PPM, top row first, channels scaled to [0, 1]
+ */
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[f32; 3]>,
+}
+
+impl Image {
+    pub fn from_frame(frame: &Frame) -> Self {
+        Self {
+            width: frame.width as usize,
+            height: frame.height as usize,
+            pixels: frame
+                .to_srgb8()
+                .iter()
+                .map(|p| [p.r, p.g, p.b].map(|c| c as f32 / 255.0))
+                .collect(),
+        }
+    }
+
+    /**
+     * Reads binary PPM (P6) with 8-bit channels
+     */
+    pub fn read_ppm(path: &Path) -> io::Result<Self> {
+        let mut reader = BufReader::new(fs::File::open(path)?);
+        let mut header = vec![];
+        // Magic, width, height and max value, each followed by a single whitespace
+        while header.len() < 4 {
+            let mut token = vec![];
+            reader.read_until(b'\n', &mut token)?;
+            header.extend(
+                String::from_utf8_lossy(&token)
+                    .split_whitespace()
+                    .map(str::to_string),
+            );
+        }
+
+        let invalid =
+            |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+        if header[0] != "P6" || header[3] != "255" {
+            return Err(invalid("expected 8-bit binary PPM"));
+        }
+        let parse = |value: &str| value.parse::<usize>().map_err(|_| invalid("invalid size"));
+        let (width, height) = (parse(&header[1])?, parse(&header[2])?);
+
+        let mut bytes = vec![0; width * height * 3];
+        reader.read_exact(&mut bytes)?;
+        Ok(Self {
+            width,
+            height,
+            pixels: bytes
+                .chunks(3)
+                .map(|p| [p[0], p[1], p[2]].map(|c| c as f32 / 255.0))
+                .collect(),
+        })
+    }
+
+    pub fn write_ppm(&self, path: &Path) -> io::Result<()> {
+        let mut file = fs::File::create(path)?;
+        write!(file, "P6\n{} {}\n255\n", self.width, self.height)?;
+        let bytes: Vec<u8> = self
+            .pixels
+            .iter()
+            .flat_map(|pixel| pixel.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8))
+            .collect();
+        file.write_all(&bytes)
+    }
+}
+
+/**
+ * Difference of a render against its reference
+ */
+pub struct Comparison {
+    pub rmse: f32,
+    /// Infinite for identical images
+    pub psnr: f32,
+    /// Mean perceptual error in [0, 1]
+    pub flip: f32,
+    /// Perceptual error of each pixel
+    pub flip_map: Vec<f32>,
+}
+
+impl Comparison {
+    pub fn new(reference: &Image, actual: &Image) -> Self {
+        let squared_error: f32 = reference
+            .pixels
+            .iter()
+            .zip(&actual.pixels)
+            .flat_map(|(r, a)| (0..3).map(move |c| (r[c] - a[c]).powi(2)))
+            .sum();
+        let rmse = (squared_error / (reference.pixels.len() * 3) as f32).sqrt();
+        let flip_map = flip::error_map(reference, actual);
+
+        Self {
+            rmse,
+            psnr: 20.0 * (1.0 / rmse).log10(),
+            flip: flip_map.iter().sum::<f32>() / flip_map.len() as f32,
+            flip_map,
+        }
+    }
+
+    fn within(&self, tolerance: &Tolerance) -> bool {
+        self.rmse <= tolerance.max_rmse
+            && self.psnr >= tolerance.min_psnr
+            && self.flip <= tolerance.max_flip
+    }
+}
+
+pub fn reference_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden/references")
+        .join(format!("{}.ppm", name))
+}
+
+/**
+ * Directory for renders and diff images of failed comparisons
+ */
+pub fn output_dir() -> PathBuf {
+    Path::new(env!("CARGO_TARGET_TMPDIR")).join("golden")
+}
+
+/**
+ * Compares frame against reference image of the same name. On failure the render is written
+ * as <name>.actual.ppm and the perceptual error as <name>.diff.ppm to output_dir.
+ */
+pub fn assert_matches_reference(name: &str, frame: &Frame, tolerance: &Tolerance) {
+    let actual = Image::from_frame(frame);
+    let reference_file = reference_path(name);
+
+    if env::var(UPDATE_VARIABLE).is_ok_and(|value| value == "1") {
+        fs::create_dir_all(reference_file.parent().unwrap()).unwrap();
+        actual.write_ppm(&reference_file).unwrap();
+        return;
+    }
+
+    let reference = Image::read_ppm(&reference_file).unwrap_or_else(|err| {
+        panic!(
+            "could not read reference {}: {}. Run with {}=1 to create it.",
+            reference_file.display(),
+            err,
+            UPDATE_VARIABLE
+        )
+    });
+    assert_eq!(
+        (reference.width, reference.height),
+        (actual.width, actual.height),
+        "render size differs from reference {}",
+        reference_file.display()
+    );
+
+    let comparison = Comparison::new(&reference, &actual);
+    if comparison.within(tolerance) {
+        return;
+    }
+
+    let output_dir = output_dir();
+    fs::create_dir_all(&output_dir).unwrap();
+    let actual_file = output_dir.join(format!("{}.actual.ppm", name));
+    let diff_file = output_dir.join(format!("{}.diff.ppm", name));
+    actual.write_ppm(&actual_file).unwrap();
+    flip::heatmap(&comparison.flip_map, actual.width, actual.height)
+        .write_ppm(&diff_file)
+        .unwrap();
+
+    panic!(
+        "{} differs from reference: RMSE {:.5} (max {}), PSNR {:.2} dB (min {}), FLIP {:.5} (max {}).\n\
+         Render written to {}, difference to {}. Run with {}=1 if the change is intended.",
+        name,
+        comparison.rmse,
+        tolerance.max_rmse,
+        comparison.psnr,
+        tolerance.min_psnr,
+        comparison.flip,
+        tolerance.max_flip,
+        actual_file.display(),
+        diff_file.display(),
+        UPDATE_VARIABLE
+    );
+}
+
+/**
+ * Simplified version of the FLIP image difference metric (Andersson et al. 2020). Colors
+ * are compared after a blur approximating the contrast sensitivity of the eye, and the
+ * error is amplified where edges differ.
+ */
+mod flip {
+    use shared::data::srgb_to_linear;
+
+    use super::Image;
+
+    /// Gaussian standard deviation in pixels, roughly one arc minute at desktop viewing distance
+    const BLUR_SIGMA: f32 = 1.0;
+    /// Exponent compressing small color differences, as in FLIP
+    const COLOR_EXPONENT: f32 = 0.7;
+
+    pub fn error_map(reference: &Image, actual: &Image) -> Vec<f32> {
+        let (width, height) = (reference.width, reference.height);
+        let lab_reference = filtered_lab(reference);
+        let lab_actual = filtered_lab(actual);
+        let max_color_error =
+            hyab(&lab(&[0.0, 1.0, 0.0]), &lab(&[0.0, 0.0, 1.0])).powf(COLOR_EXPONENT);
+
+        let edges_reference = edges(&lab_reference, width, height);
+        let edges_actual = edges(&lab_actual, width, height);
+
+        (0..width * height)
+            .map(|i| {
+                let color_error = (hyab(&lab_reference[i], &lab_actual[i]).powf(COLOR_EXPONENT)
+                    / max_color_error)
+                    .min(1.0);
+                let feature_error = (edges_reference[i] - edges_actual[i]).abs().min(1.0);
+                color_error.powf(1.0 - feature_error)
+            })
+            .collect()
+    }
+
+    /**
+     * Error mapped to black, red, yellow, white
+     */
+    pub fn heatmap(errors: &[f32], width: usize, height: usize) -> Image {
+        Image {
+            width,
+            height,
+            pixels: errors
+                .iter()
+                .map(|error| {
+                    let e = error.clamp(0.0, 1.0) * 3.0;
+                    [e, e - 1.0, e - 2.0].map(|c| c.clamp(0.0, 1.0))
+                })
+                .collect(),
+        }
+    }
+
+    /**
+     * Linear RGB of sRGB encoded pixel
+     */
+    fn linear(pixel: &[f32; 3]) -> [f32; 3] {
+        pixel.map(srgb_to_linear)
+    }
+
+    fn xyz(rgb: &[f32; 3]) -> [f32; 3] {
+        let [r, g, b] = *rgb;
+        [
+            0.4124 * r + 0.3576 * g + 0.1805 * b,
+            0.2126 * r + 0.7152 * g + 0.0722 * b,
+            0.0193 * r + 0.1192 * g + 0.9505 * b,
+        ]
+    }
+
+    /**
+     * CIELAB of linear RGB, D65 white point
+     */
+    fn lab(rgb: &[f32; 3]) -> [f32; 3] {
+        let [x, y, z] = xyz(rgb);
+        let f = |t: f32| match t > 0.008856 {
+            true => t.cbrt(),
+            false => 7.787 * t + 16.0 / 116.0,
+        };
+        let (fx, fy, fz) = (f(x / 0.9505), f(y), f(z / 1.089));
+        [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+    }
+
+    /**
+     * Lab of image after blurring in linear color
+     */
+    fn filtered_lab(image: &Image) -> Vec<[f32; 3]> {
+        let linear: Vec<[f32; 3]> = image.pixels.iter().map(linear).collect();
+        blur(&linear, image.width, image.height)
+            .iter()
+            .map(lab)
+            .collect()
+    }
+
+    /**
+     * Separable Gaussian blur, edges clamped
+     */
+    fn blur(pixels: &[[f32; 3]], width: usize, height: usize) -> Vec<[f32; 3]> {
+        let radius = (3.0 * BLUR_SIGMA).ceil() as i32;
+        let kernel: Vec<f32> = (-radius..=radius)
+            .map(|x| (-(x * x) as f32 / (2.0 * BLUR_SIGMA * BLUR_SIGMA)).exp())
+            .collect();
+        let kernel_sum: f32 = kernel.iter().sum();
+
+        let pass = |source: &[[f32; 3]], horizontal: bool| -> Vec<[f32; 3]> {
+            (0..width * height)
+                .map(|i| {
+                    let (x, y) = ((i % width) as i32, (i / width) as i32);
+                    let mut sum = [0.0; 3];
+                    for (offset, weight) in (-radius..=radius).zip(&kernel) {
+                        let (sx, sy) = match horizontal {
+                            true => ((x + offset).clamp(0, width as i32 - 1), y),
+                            false => (x, (y + offset).clamp(0, height as i32 - 1)),
+                        };
+                        let pixel = source[sy as usize * width + sx as usize];
+                        for c in 0..3 {
+                            sum[c] += pixel[c] * weight / kernel_sum;
+                        }
+                    }
+                    sum
+                })
+                .collect()
+        };
+
+        pass(&pass(pixels, true), false)
+    }
+
+    /**
+     * Hybrid distance, absolute lightness difference plus Euclidean chroma difference
+     */
+    fn hyab(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+        (a[0] - b[0]).abs() + ((a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+    }
+
+    /**
+     * Sobel gradient magnitude of lightness, scaled to about [0, 1]
+     */
+    fn edges(lab: &[[f32; 3]], width: usize, height: usize) -> Vec<f32> {
+        let lightness = |x: i32, y: i32| {
+            let x = x.clamp(0, width as i32 - 1) as usize;
+            let y = y.clamp(0, height as i32 - 1) as usize;
+            lab[y * width + x][0] / 100.0
+        };
+
+        (0..width * height)
+            .map(|i| {
+                let (x, y) = ((i % width) as i32, (i / width) as i32);
+                let gx =
+                    lightness(x + 1, y - 1) + 2.0 * lightness(x + 1, y) + lightness(x + 1, y + 1)
+                        - lightness(x - 1, y - 1)
+                        - 2.0 * lightness(x - 1, y)
+                        - lightness(x - 1, y + 1);
+                let gy =
+                    lightness(x - 1, y + 1) + 2.0 * lightness(x, y + 1) + lightness(x + 1, y + 1)
+                        - lightness(x - 1, y - 1)
+                        - 2.0 * lightness(x, y - 1)
+                        - lightness(x + 1, y - 1);
+                (gx * gx + gy * gy).sqrt() / 4.0
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,90 @@
+mod golden;
+
+use golden::{assert_matches_reference, Image, Tolerance};
+use renderer::{
+    renderer::{denoise::DenoiseMethod, filter::FilterType},
+    CameraDescription, Integrator, Render, RenderSettings, Renderer, Scene,
+};
+use shared::data::Frame;
+
+const SEED: u64 = 2023;
+const WIDTH: i32 = 64;
+const HEIGHT: i32 = 36;
+const SAMPLES_PER_PIXEL: i16 = 16;
+
+fn settings() -> RenderSettings {
+    RenderSettings {
+        width: WIDTH,
+        height: HEIGHT,
+        samples_per_pixel: SAMPLES_PER_PIXEL,
+        seed: Some(SEED),
+        ..Default::default()
+    }
+}
+
+fn render(renderer: Renderer) -> Frame {
+    renderer.render(&settings()).unwrap()
+}
+
+fn preset(name: &str) -> CameraDescription {
+    CameraDescription::find(&Scene::default_cameras(), Some(name))
+        .unwrap()
+        .clone()
+}
+
+#[test]
+fn seeded_renders_are_identical() {
+    let first = Image::from_frame(&render(Renderer::default()));
+    let second = Image::from_frame(&render(Renderer::default()));
+    assert_eq!(first.pixels, second.pixels);
+}
+
+#[test]
+fn default_scene() {
+    let frame = render(Renderer::default());
+    assert_matches_reference("default_scene", &frame, &Tolerance::default());
+}
+
+#[test]
+fn front_camera_mitchell_filter() {
+    let frame = render(
+        Renderer::default()
+            .with_camera(preset("front"))
+            .with_filter("mitchell".parse::<FilterType>().unwrap()),
+    );
+    assert_matches_reference("front_camera_mitchell", &frame, &Tolerance::default());
+}
+
+#[test]
+fn orthographic_camera() {
+    let frame = render(Renderer::default().with_camera(preset("top")));
+    assert_matches_reference("orthographic", &frame, &Tolerance::default());
+}
+
+#[test]
+fn equirectangular_camera() {
+    let frame = render(Renderer::default().with_camera(preset("panorama")));
+    assert_matches_reference("equirectangular", &frame, &Tolerance::default());
+}
+
+#[test]
+fn spectral() {
+    let settings = RenderSettings {
+        integrator: Integrator::Spectral,
+        ..settings()
+    };
+    let frame = Renderer::default().render(&settings).unwrap();
+    assert_matches_reference("spectral", &frame, &Tolerance::default());
+}
+
+#[test]
+fn atrous_denoise() {
+    let frame = render(Renderer::default().with_denoise(Some(DenoiseMethod::Atrous)));
+    assert_matches_reference("atrous_denoise", &frame, &Tolerance::default());
+}
+
+#[test]
+fn random_scene() {
+    let frame = render(Renderer::default().with_scene(Scene::random(SEED)));
+    assert_matches_reference("random_scene", &frame, &Tolerance::default());
+}
@@ -0,0 +1,52 @@
+use std::fs;
+
+use renderer::{
+    textures::{image::ImageTexture, texture::Texture},
+    util::point::Point,
+};
+use shared::data::{Frame, Pixel};
+
+/**
+ * Writes PPM to a file unique to this test process and loads it as texture
+ */
+fn load_ppm(name: &str, content: &str) -> std::io::Result<ImageTexture> {
+    let path = std::env::temp_dir().join(format!("renderer-{}-{}.ppm", name, std::process::id()));
+    fs::write(&path, content).unwrap();
+    let texture = ImageTexture::from_ppm(&path.to_string_lossy());
+    fs::remove_file(&path).unwrap();
+    texture
+}
+
+#[test]
+fn srgb_texture_round_trips_written_color() {
+    let color = [0.002, 0.2, 0.75];
+    let mut frame = Frame::new(1, 1);
+    frame.pixels[0] = Pixel::opaque(color[0], color[1], color[2]);
+    let encoded = &frame.to_srgb16()[0];
+
+    let content = format!(
+        "P3\n1 1\n65535\n{} {} {}\n",
+        encoded.r, encoded.g, encoded.b
+    );
+    let texture = load_ppm("srgb", &content).unwrap().decode_srgb();
+    let decoded = texture.value(0.5, 0.5, &Point::default());
+
+    for (decoded, color) in decoded.e.iter().zip(color) {
+        assert!(
+            (decoded - color).abs() < 1e-4,
+            "{} decoded as {}",
+            color,
+            decoded
+        );
+    }
+}
+
+#[test]
+fn empty_ppm_is_an_error() {
+    for content in ["P3\n0 0\n255\n", "P3\n0 4\n255\n", "P6\n4 0\n255\n"] {
+        let err = load_ppm("empty", content)
+            .err()
+            .expect("empty image loaded");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,146 @@
+use std::path::Path;
+
+use renderer::{Checkpoint, CropOutput, CropWindow, Render, RenderError, RenderSettings, Renderer};
+use shared::data::{Frame, Pixel};
+
+const WIDTH: i32 = 40;
+const HEIGHT: i32 = 24;
+
+const CROP: CropWindow = CropWindow::Pixels {
+    x: 12,
+    y: 5,
+    width: 10,
+    height: 7,
+};
+
+fn settings() -> RenderSettings {
+    RenderSettings {
+        width: WIDTH,
+        height: HEIGHT,
+        samples_per_pixel: 4,
+        max_depth: 8,
+        seed: Some(7),
+        ..Default::default()
+    }
+}
+
+fn cropped(crop_output: CropOutput) -> RenderSettings {
+    RenderSettings {
+        crop: Some(CROP),
+        crop_output,
+        ..settings()
+    }
+}
+
+fn inside_crop(x: i32, y: i32) -> bool {
+    let (columns, rows) = CROP.pixel_ranges(WIDTH, HEIGHT);
+    columns.contains(&x) && rows.contains(&y)
+}
+
+fn pixels(frame: &Frame) -> impl Iterator<Item = (i32, i32, &Pixel)> {
+    (0..frame.height).flat_map(move |y| (0..frame.width).map(move |x| (x, y, frame.pixel(x, y))))
+}
+
+#[test]
+fn cropped_image_matches_full_render() {
+    let full = Renderer::default().render(&settings()).unwrap();
+    let crop = Renderer::default()
+        .render(&cropped(CropOutput::Cropped))
+        .unwrap();
+
+    assert_eq!((crop.width, crop.height), (10, 7));
+    for (x, y, pixel) in pixels(&crop) {
+        assert_eq!(pixel, full.pixel(x + 12, y + 5), "pixel ({}, {})", x, y);
+    }
+}
+
+#[test]
+fn full_frame_output_leaves_rest_empty() {
+    let full = Renderer::default().render(&settings()).unwrap();
+    let frame = Renderer::default()
+        .render(&cropped(CropOutput::FullFrame))
+        .unwrap();
+
+    assert_eq!((frame.width, frame.height), (WIDTH, HEIGHT));
+    for (x, y, pixel) in pixels(&frame) {
+        match inside_crop(x, y) {
+            true => assert_eq!(pixel, full.pixel(x, y), "pixel ({}, {})", x, y),
+            false => assert_eq!(*pixel, Pixel::default(), "pixel ({}, {})", x, y),
+        }
+    }
+}
+
+#[test]
+fn normalized_crop_covers_overlapped_pixels() {
+    let crop = CropWindow::Normalized {
+        x: 0.26,
+        y: 0.5,
+        width: 0.5,
+        height: 0.25,
+    };
+    assert_eq!(crop.pixel_ranges(WIDTH, HEIGHT), (10..31, 12..18));
+
+    let frame = Renderer::default()
+        .render(&RenderSettings {
+            crop: Some(crop),
+            ..settings()
+        })
+        .unwrap();
+    assert_eq!((frame.width, frame.height), (21, 6));
+}
+
+#[test]
+fn resumed_checkpoint_refines_crop_only() {
+    let renderer = Renderer::default();
+    let (first, checkpoint) = renderer.render_resumed(&settings(), None).unwrap();
+    assert_eq!(checkpoint.passes(), 1);
+
+    let (refined, checkpoint) = renderer
+        .render_resumed(&cropped(CropOutput::FullFrame), Some(checkpoint))
+        .unwrap();
+    assert_eq!(checkpoint.passes(), 2);
+
+    let mut changed = 0;
+    for (x, y, pixel) in pixels(&refined) {
+        match inside_crop(x, y) {
+            true => changed += (pixel != first.pixel(x, y)) as usize,
+            false => assert_eq!(pixel, first.pixel(x, y), "pixel ({}, {})", x, y),
+        }
+    }
+    assert!(
+        changed > 35,
+        "only {} of 70 cropped pixels changed",
+        changed
+    );
+}
+
+#[test]
+fn checkpoint_survives_save_and_load() {
+    let renderer = Renderer::default();
+    let (_, checkpoint) = renderer.render_resumed(&settings(), None).unwrap();
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("region.checkpoint");
+    checkpoint.save(&path).unwrap();
+    let loaded = Checkpoint::load(&path).unwrap();
+    assert_eq!(loaded.passes(), 1);
+
+    let (resumed, _) = renderer
+        .render_resumed(&settings(), Some(checkpoint))
+        .unwrap();
+    let (reloaded, _) = renderer.render_resumed(&settings(), Some(loaded)).unwrap();
+    assert_eq!(resumed.pixels, reloaded.pixels);
+}
+
+#[test]
+fn checkpoint_of_other_size_is_rejected() {
+    let renderer = Renderer::default();
+    let (_, checkpoint) = renderer.render_resumed(&settings(), None).unwrap();
+    let larger = RenderSettings {
+        width: WIDTH * 2,
+        ..settings()
+    };
+
+    assert!(matches!(
+        renderer.render_resumed(&larger, Some(checkpoint)),
+        Err(RenderError::InvalidSettings(_))
+    ));
+}
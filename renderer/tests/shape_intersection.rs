@@ -0,0 +1,101 @@
+mod shapes;
+
+use proptest::prelude::*;
+use renderer::{
+    shapes::{hit_collection::HitCollection, traits::Hit},
+    util::ray::Ray,
+};
+use shapes::{
+    check_hit, check_interval, check_no_self_intersection, new_sphere, new_triangle, point,
+    ray_towards, sphere, sphere_and_ray, triangle, triangle_and_ray, unit_vector, EXTENT,
+};
+
+proptest! {
+    #[test]
+    fn sphere_hits_lie_on_surface((sphere, (origin, direction)) in sphere_and_ray()) {
+        check_hit(&new_sphere(sphere), &Ray::new(origin, direction), 0.0, f32::INFINITY)?;
+    }
+
+    #[test]
+    fn sphere_respects_interval(
+        (sphere, (origin, direction)) in sphere_and_ray(),
+        t_min in 0.0f32..EXTENT,
+        span in 0.0f32..EXTENT,
+    ) {
+        let ray = Ray::new(origin, direction);
+        let t_min = t_min / direction.length();
+        check_interval(&new_sphere(sphere), &ray, t_min, t_min + span / direction.length())?;
+    }
+
+    #[test]
+    fn sphere_has_no_self_intersection(
+        (sphere, (origin, direction)) in sphere_and_ray(),
+        bounce in unit_vector(),
+    ) {
+        let sphere = new_sphere(sphere);
+        let record = check_hit(&sphere, &Ray::new(origin, direction), 0.0, f32::INFINITY)?;
+        if let Some(record) = record {
+            check_no_self_intersection(&sphere, &record, &bounce)?;
+        }
+    }
+
+    #[test]
+    fn triangle_hits_lie_on_surface((triangle, (origin, direction)) in triangle_and_ray()) {
+        check_hit(&new_triangle(triangle), &Ray::new(origin, direction), 0.0, f32::INFINITY)?;
+    }
+
+    #[test]
+    fn triangle_respects_interval(
+        (triangle, (origin, direction)) in triangle_and_ray(),
+        t_min in 0.0f32..EXTENT,
+        span in 0.0f32..EXTENT,
+    ) {
+        let ray = Ray::new(origin, direction);
+        let t_min = t_min / direction.length();
+        check_interval(&new_triangle(triangle), &ray, t_min, t_min + span / direction.length())?;
+    }
+
+    #[test]
+    fn triangle_has_no_self_intersection(
+        (triangle, (origin, direction)) in triangle_and_ray(),
+        bounce in unit_vector(),
+    ) {
+        let triangle = new_triangle(triangle);
+        let record = check_hit(&triangle, &Ray::new(origin, direction), 0.0, f32::INFINITY)?;
+        if let Some(record) = record {
+            check_no_self_intersection(&triangle, &record, &bounce)?;
+        }
+    }
+
+    /**
+     * Collection reports the nearest of its objects' hits with that object's index
+     */
+    #[test]
+    fn collection_returns_closest_hit(
+        spheres in prop::collection::vec(sphere(), 1..8),
+        triangles in prop::collection::vec(triangle(), 0..8),
+        (origin, direction) in point().prop_flat_map(ray_towards),
+        t_min in 0.0f32..1.0,
+    ) {
+        let ray = Ray::new(origin, direction);
+        let mut collection = HitCollection::default();
+        let mut objects: Vec<Box<dyn Hit + Sync>> = vec![];
+        for sphere in &spheres {
+            collection.add(Box::new(new_sphere(*sphere)));
+            objects.push(Box::new(new_sphere(*sphere)));
+        }
+        for triangle in &triangles {
+            collection.add(Box::new(new_triangle(*triangle)));
+            objects.push(Box::new(new_triangle(*triangle)));
+        }
+
+        let closest = objects
+            .iter()
+            .enumerate()
+            .filter_map(|(index, object)| Some((index, object.hit(&ray, t_min, f32::INFINITY)?.t)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        let record = collection.hit(&ray, t_min, f32::INFINITY);
+        prop_assert_eq!(record.map(|record| (record.object_id, record.t)), closest);
+    }
+}
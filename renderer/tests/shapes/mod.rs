@@ -0,0 +1,290 @@
+use proptest::prelude::*;
+use renderer::{
+    materials::{lambertian::Lambertian, material::MaterialType},
+    math::vec3::Vec3,
+    shapes::{hit_record::HitRecord, sphere::Sphere, traits::Hit, triangle::Triangle},
+    util::{color::Color, point::Point, ray::Ray},
+};
+
+/// Scene coordinates are drawn from this range
+pub const EXTENT: f32 = 100.0;
+/// Error allowed relative to the magnitude of the coordinates involved
+const RELATIVE_TOLERANCE: f32 = 1e-4;
+/// Secondary rays start this far from the surface, relative to coordinate magnitude
+const RELATIVE_OFFSET: f32 = 1e-4;
+/// Secondary rays leave at least this steeply, grazing rays can hit curved surfaces right away
+const MIN_LEAVING_COS: f32 = 0.1;
+
+/**
+ * Shape with a known implicit surface, used to validate what Hit reports
+ */
+pub trait Surface: Hit {
+    /**
+     * Distance of point from the surface
+     */
+    fn distance(&self, point: &Point) -> f32;
+
+    /**
+     * Unit normal pointing out of the shape, or to the front side of flat shapes
+     */
+    fn outward_normal(&self, point: &Point) -> Vec3;
+
+    /**
+     * Magnitude of the coordinates describing the shape, tolerances scale with it
+     */
+    fn magnitude(&self) -> f32;
+
+    /**
+     * Shortest distance a ray leaving the surface at a steep angle travels before it can
+     * legitimately hit the surface again
+     */
+    fn feature_size(&self) -> f32;
+}
+
+impl Surface for Sphere {
+    fn distance(&self, point: &Point) -> f32 {
+        ((point - self.center).length() - self.radius).abs()
+    }
+
+    fn outward_normal(&self, point: &Point) -> Vec3 {
+        (point - self.center).unit_vector()
+    }
+
+    fn magnitude(&self) -> f32 {
+        self.center.length() + self.radius
+    }
+
+    fn feature_size(&self) -> f32 {
+        // Shortest chord at the steepest angle allowed when leaving
+        2.0 * MIN_LEAVING_COS * self.radius
+    }
+}
+
+impl Surface for Triangle {
+    fn distance(&self, point: &Point) -> f32 {
+        self.outward_normal(point)
+            .dot(&(point - self.vertices[0]))
+            .abs()
+    }
+
+    fn outward_normal(&self, _: &Point) -> Vec3 {
+        let [a, b, c] = self.vertices;
+        (b - a).cross(&(c - a)).unit_vector()
+    }
+
+    fn magnitude(&self) -> f32 {
+        self.vertices.iter().map(Vec3::length).fold(0.0, f32::max)
+    }
+
+    fn feature_size(&self) -> f32 {
+        f32::INFINITY
+    }
+}
+
+pub fn new_sphere((center, radius): (Point, f32)) -> Sphere {
+    Sphere::new(center, radius, material())
+}
+
+pub fn new_triangle(vertices: [Point; 3]) -> Triangle {
+    Triangle::new(vertices, [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]], material())
+}
+
+fn material() -> MaterialType {
+    MaterialType::Lambertian(Lambertian {
+        albedo: Color::new([0.5, 0.5, 0.5]),
+    })
+}
+
+fn tolerance(shape: &impl Surface, ray: &Ray) -> f32 {
+    RELATIVE_TOLERANCE * shape.magnitude().max(ray.origin().length()).max(1.0)
+}
+
+/**
+ * Checks a single intersection query. A reported hit must lie on the surface at the returned
+ * ray parameter inside [t_min, t_max], with a unit normal facing the incoming ray.
+ */
+pub fn check_hit(
+    shape: &impl Surface,
+    ray: &Ray,
+    t_min: f32,
+    t_max: f32,
+) -> Result<Option<HitRecord>, TestCaseError> {
+    let Some(record) = shape.hit(ray, t_min, t_max) else {
+        return Ok(None);
+    };
+    let tolerance = tolerance(shape, ray);
+
+    prop_assert!(
+        t_min <= record.t && record.t <= t_max,
+        "t {} outside [{}, {}]",
+        record.t,
+        t_min,
+        t_max
+    );
+    prop_assert!((record.point - ray.at(record.t)).length() <= tolerance);
+    prop_assert!(
+        shape.distance(&record.point) <= tolerance,
+        "hit point {:?} is {} from the surface",
+        record.point.e,
+        shape.distance(&record.point)
+    );
+
+    prop_assert!((record.normal.length() - 1.0).abs() < 1e-3);
+    prop_assert!((record.tangent.length() - 1.0).abs() < 1e-3);
+    prop_assert!(record.normal.dot(&record.tangent).abs() < 1e-3);
+
+    // Normal points to the side the ray comes from, front face is the outward side
+    let direction = ray.direction().unit_vector();
+    let outward = shape.outward_normal(&record.point);
+    prop_assert!(
+        record.normal.dot(&direction) <= 1e-3,
+        "normal {:?} faces away from ray {:?}",
+        record.normal.e,
+        direction.e
+    );
+    prop_assert_eq!(record.front_face, outward.dot(&direction) < 0.0);
+    let expected_normal = if record.front_face { outward } else { -outward };
+    prop_assert!((record.normal - expected_normal).length() < 1e-3);
+
+    Ok(Some(record))
+}
+
+/**
+ * A query restricted to [t_min, t_max] returns the nearest surface crossing in the range:
+ * never one outside it, never a farther one than the unrestricted query finds inside it
+ */
+pub fn check_interval(
+    shape: &impl Surface,
+    ray: &Ray,
+    t_min: f32,
+    t_max: f32,
+) -> Result<(), TestCaseError> {
+    let restricted = check_hit(shape, ray, t_min, t_max)?;
+    let Some(nearest) = check_hit(shape, ray, 0.0, f32::INFINITY)? else {
+        prop_assert!(restricted.is_none(), "hit in range but not without range");
+        return Ok(());
+    };
+
+    let slack = tolerance(shape, ray) / ray.direction().length();
+    if t_min <= nearest.t - slack && nearest.t + slack <= t_max {
+        let restricted = restricted.map(|record| record.t);
+        prop_assert!(
+            restricted.is_some_and(|t| (t - nearest.t).abs() <= slack),
+            "nearest hit at {} in [{}, {}], restricted query returned {:?}",
+            nearest.t,
+            t_min,
+            t_max,
+            restricted
+        );
+    }
+    Ok(())
+}
+
+/**
+ * Rays leaving a hit point on the side of its normal, from just outside the surface, must
+ * not hit the surface they start on
+ */
+pub fn check_no_self_intersection(
+    shape: &impl Surface,
+    record: &HitRecord,
+    direction: &Vec3,
+) -> Result<(), TestCaseError> {
+    let direction = match direction.dot(&record.normal) < 0.0 {
+        true => -*direction,
+        false => *direction,
+    };
+    // Tilted towards the normal so the cosine stays above MIN_LEAVING_COS
+    let direction = (direction + record.normal * (2.0 * MIN_LEAVING_COS)).unit_vector();
+
+    let offset = RELATIVE_OFFSET * shape.magnitude().max(record.point.length()).max(1.0);
+    // A hit this close can't be told apart from the surface the ray started on
+    let self_distance = offset / MIN_LEAVING_COS;
+    if self_distance >= shape.feature_size() {
+        return Ok(());
+    }
+
+    let origin = record.point + record.normal * offset;
+    let ray = Ray::new(origin, direction);
+
+    if let Some(second) = check_hit(shape, &ray, 0.0, f32::INFINITY)? {
+        prop_assert!(
+            (second.point - record.point).length() > self_distance,
+            "ray leaving {:?} along {:?} hit the surface again at t {}",
+            record.point.e,
+            direction.e,
+            second.t
+        );
+    }
+    Ok(())
+}
+
+prop_compose! {
+    pub fn point()(x in -EXTENT..EXTENT, y in -EXTENT..EXTENT, z in -EXTENT..EXTENT) -> Point {
+        Point::new([x, y, z])
+    }
+}
+
+prop_compose! {
+    pub fn unit_vector()(
+        v in prop::array::uniform3(-1.0f32..1.0)
+            .prop_filter("near zero", |v| Vec3::new(*v).length() > 0.1)
+    ) -> Vec3 {
+        Vec3::new(v).unit_vector()
+    }
+}
+
+/**
+ * Sphere center and radius
+ */
+pub fn sphere() -> impl Strategy<Value = (Point, f32)> {
+    (point(), 0.01f32..EXTENT)
+}
+
+/**
+ * Triangle vertices with angles large enough that the normal is well defined
+ */
+pub fn triangle() -> impl Strategy<Value = [Point; 3]> {
+    [point(), point(), point()].prop_filter("degenerate", |[a, b, c]| {
+        let area = (b - a).cross(&(c - a)).length();
+        let longest = [b - a, c - b, a - c]
+            .iter()
+            .map(Vec3::length_squared)
+            .fold(0.0, f32::max);
+        area > 0.05 * longest
+    })
+}
+
+prop_compose! {
+    /**
+     * Origin and direction of a ray through target, from random direction and distance.
+     * Direction length varies so shapes can't rely on it being normalized.
+     */
+    pub fn ray_towards(target: Point)(
+        direction in unit_vector(),
+        distance in 0.0f32..2.0 * EXTENT,
+        length in 0.1f32..10.0,
+    ) -> (Point, Vec3) {
+        (target - direction * distance, direction * length)
+    }
+}
+
+/**
+ * Sphere and a ray aimed near it, some pass beside it
+ */
+pub fn sphere_and_ray() -> impl Strategy<Value = ((Point, f32), (Point, Vec3))> {
+    (sphere(), unit_vector(), 0.0f32..1.2).prop_flat_map(|((center, radius), offset, scale)| {
+        let target = center + offset * (radius * scale);
+        (Just((center, radius)), ray_towards(target))
+    })
+}
+
+/**
+ * Triangle and a ray aimed near it, some pass beside it
+ */
+pub fn triangle_and_ray() -> impl Strategy<Value = ([Point; 3], (Point, Vec3))> {
+    (triangle(), -0.2f32..1.2, -0.2f32..1.2).prop_flat_map(|(vertices, b1, b2)| {
+        let [a, b, c] = vertices;
+        let target = a + (b - a) * b1 + (c - a) * b2;
+        (Just(vertices), ray_towards(target))
+    })
+}
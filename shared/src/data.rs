@@ -1,14 +1,167 @@
 /**
- * Output image/frame from renderer
+ * Output image/frame from renderer. Pixels are linear and unclamped, rows are stored from
+ * bottom to top. Use the row helpers and encoders for the usual top to bottom order.
  */
 pub struct Frame {
     pub pixels: Vec<Pixel>,
     pub height: i32,
     pub width: i32,
+    /// Extra render passes, empty unless requested from renderer
+    pub aovs: Vec<Aov>,
 }
 
+impl Frame {
+    /**
+     * Transparent black frame
+     */
+    pub fn new(width: i32, height: i32) -> Self {
+        Self {
+            pixels: vec![Pixel::default(); (width * height) as usize],
+            height,
+            width,
+            aovs: vec![],
+        }
+    }
+
+    pub fn aov(&self, name: &str) -> Option<&Aov> {
+        self.aovs.iter().find(|aov| aov.name == name)
+    }
+
+    /**
+     * Index to pixels and AOV values of (x, y) counted from the top left corner
+     */
+    pub fn index(&self, x: i32, y: i32) -> usize {
+        ((self.height - 1 - y) * self.width + x) as usize
+    }
+
+    /**
+     * Pixel at (x, y) counted from the top left corner
+     */
+    pub fn pixel(&self, x: i32, y: i32) -> &Pixel {
+        &self.pixels[self.index(x, y)]
+    }
+
+    /**
+     * Rows from top to bottom
+     */
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[Pixel]> + ExactSizeIterator {
+        self.pixels.chunks(self.width as usize).rev()
+    }
+
+    /**
+     * Pixels in given format, top row first
+     */
+    pub fn encode<F: PixelFormat>(&self) -> Vec<F> {
+        self.rows().flatten().map(F::encode).collect()
+    }
+
+    pub fn to_srgb8(&self) -> Vec<Srgb8> {
+        self.encode()
+    }
+
+    pub fn to_srgb16(&self) -> Vec<Srgb16> {
+        self.encode()
+    }
+}
+
+/**
+ * Linear RGB with alpha. Alpha is the share of camera rays hitting geometry, color is
+ * not premultiplied by it.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Pixel {
-    pub r: f64,
-    pub g: f64,
-    pub b: f64,
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl Pixel {
+    pub fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    pub fn opaque(r: f32, g: f32, b: f32) -> Self {
+        Self::new(r, g, b, 1.0)
+    }
+}
+
+/**
+ * Integer RGBA pixel. Color channels are sRGB encoded, alpha is linear.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rgba<T> {
+    pub r: T,
+    pub g: T,
+    pub b: T,
+    pub a: T,
+}
+
+pub type Srgb8 = Rgba<u8>;
+pub type Srgb16 = Rgba<u16>;
+
+/**
+ * Storage format frame pixels can be encoded to
+ */
+pub trait PixelFormat {
+    fn encode(pixel: &Pixel) -> Self;
+}
+
+impl PixelFormat for Srgb8 {
+    fn encode(pixel: &Pixel) -> Self {
+        let quantize = |value: f32| (value * u8::MAX as f32).round() as u8;
+        encode_srgb(pixel, quantize)
+    }
+}
+
+impl PixelFormat for Srgb16 {
+    fn encode(pixel: &Pixel) -> Self {
+        let quantize = |value: f32| (value * u16::MAX as f32).round() as u16;
+        encode_srgb(pixel, quantize)
+    }
+}
+
+fn encode_srgb<T>(pixel: &Pixel, quantize: impl Fn(f32) -> T) -> Rgba<T> {
+    Rgba {
+        r: quantize(linear_to_srgb(pixel.r)),
+        g: quantize(linear_to_srgb(pixel.g)),
+        b: quantize(linear_to_srgb(pixel.b)),
+        a: quantize(pixel.a.clamp(0.0, 1.0)),
+    }
+}
+
+/**
+ * sRGB transfer function, values outside [0, 1] are clamped
+ */
+pub fn linear_to_srgb(value: f32) -> f32 {
+    let value = value.clamp(0.0, 1.0);
+    match value <= 0.0031308 {
+        true => 12.92 * value,
+        false => 1.055 * value.powf(1.0 / 2.4) - 0.055,
+    }
+}
+
+pub fn srgb_to_linear(value: f32) -> f32 {
+    match value <= 0.04045 {
+        true => value / 12.92,
+        false => ((value + 0.055) / 1.055).powf(2.4),
+    }
+}
+
+/**
+ * Arbitrary output variable, named render pass stored next to the beauty image
+ */
+pub struct Aov {
+    pub name: String,
+    /// Name of each channel, for example ["X", "Y", "Z"]
+    pub channels: Vec<String>,
+    /// Channel values interleaved per pixel, pixels in same order as Frame::pixels
+    pub values: Vec<f32>,
+}
+
+impl Aov {
+    pub fn pixel(&self, index: usize) -> &[f32] {
+        let channels = self.channels.len();
+        &self.values[index * channels..(index + 1) * channels]
+    }
 }
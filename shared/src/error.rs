@@ -0,0 +1,26 @@
+use std::fmt;
+
+/**
+ * Reason a render could not be produced
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub enum RenderError {
+    /// Settings out of range, like zero samples or a crop window outside the frame
+    InvalidSettings(String),
+    /// Settings are valid but this renderer can't honour them
+    Unsupported(String),
+    /// Reading or writing settings or output failed
+    Io(String),
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderError::InvalidSettings(message) => write!(f, "invalid settings: {}", message),
+            RenderError::Unsupported(message) => write!(f, "unsupported: {}", message),
+            RenderError::Io(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
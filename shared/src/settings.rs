@@ -0,0 +1,228 @@
+use std::{fs, ops::Range, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::RenderError;
+
+/**
+ * Everything needed to render a frame of a scene, shared by CLI and GUI and stored as JSON
+ */
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RenderSettings {
+    pub width: i32,
+    pub height: i32,
+    pub samples_per_pixel: i16,
+    /// Bounces before a path is terminated
+    pub max_depth: i16,
+    /// Fixed seed makes renders reproducible, None seeds from entropy
+    pub seed: Option<u64>,
+    /// Worker threads, None uses every core
+    pub threads: Option<usize>,
+    /// Part of the frame to trace, None traces all of it
+    pub crop: Option<CropWindow>,
+    pub crop_output: CropOutput,
+    pub integrator: Integrator,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            width: 400,
+            height: 225,
+            samples_per_pixel: 10,
+            max_depth: 50,
+            seed: None,
+            threads: None,
+            crop: None,
+            crop_output: CropOutput::default(),
+            integrator: Integrator::default(),
+        }
+    }
+}
+
+/**
+ * Color representation used while tracing paths. Output is always RGB.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Integrator {
+    #[default]
+    Rgb,
+    /// Traces sampled wavelengths, required for dispersion
+    Spectral,
+}
+
+/**
+ * Rectangle of the frame counted from the top left corner, either in pixels or as fractions
+ * of the frame size
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "unit", rename_all = "snake_case")]
+pub enum CropWindow {
+    Pixels {
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    },
+    Normalized {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    },
+}
+
+impl RenderSettings {
+    pub fn load(path: &Path) -> Result<Self, RenderError> {
+        let json = fs::read_to_string(path).map_err(|err| {
+            RenderError::Io(format!("could not read {}: {}", path.display(), err))
+        })?;
+        let settings: RenderSettings = serde_json::from_str(&json)
+            .map_err(|err| RenderError::InvalidSettings(format!("{}: {}", path.display(), err)))?;
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), RenderError> {
+        let json =
+            serde_json::to_string_pretty(self).map_err(|err| RenderError::Io(err.to_string()))?;
+        fs::write(path, json)
+            .map_err(|err| RenderError::Io(format!("could not write {}: {}", path.display(), err)))
+    }
+
+    /**
+     * Checks ranges every renderer relies on
+     */
+    pub fn validate(&self) -> Result<(), RenderError> {
+        let invalid = |message: String| Err(RenderError::InvalidSettings(message));
+
+        if self.width < 2 || self.height < 2 {
+            return invalid(format!(
+                "resolution must be at least 2x2, got {}x{}",
+                self.width, self.height
+            ));
+        }
+        if self.samples_per_pixel < 1 {
+            return invalid(format!(
+                "samples per pixel must be positive, got {}",
+                self.samples_per_pixel
+            ));
+        }
+        if self.max_depth < 1 {
+            return invalid(format!(
+                "max depth must be positive, got {}",
+                self.max_depth
+            ));
+        }
+        if self.threads == Some(0) {
+            return invalid("thread count must be positive".to_string());
+        }
+        if let Some(crop) = &self.crop {
+            crop.validate(self.width, self.height)?;
+        }
+        Ok(())
+    }
+
+    /**
+     * Size of the rendered image, the crop window size unless the output is full frame
+     */
+    pub fn output_size(&self) -> (i32, i32) {
+        match (&self.crop, self.crop_output) {
+            (Some(crop), CropOutput::Cropped) => {
+                let (columns, rows) = crop.pixel_ranges(self.width, self.height);
+                (columns.len() as i32, rows.len() as i32)
+            }
+            _ => (self.width, self.height),
+        }
+    }
+}
+
+/**
+ * Size of the image rendered with a crop window
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CropOutput {
+    /// Image of the crop window size
+    #[default]
+    Cropped,
+    /// Image of the frame size, pixels outside the crop window are left empty
+    FullFrame,
+}
+
+impl CropWindow {
+    /**
+     * Columns and rows covered in a frame of given size, clipped to the frame. Normalized
+     * windows cover every pixel they overlap.
+     */
+    pub fn pixel_ranges(&self, frame_width: i32, frame_height: i32) -> (Range<i32>, Range<i32>) {
+        match *self {
+            CropWindow::Pixels {
+                x,
+                y,
+                width,
+                height,
+            } => {
+                let range = |start: i32, size: i32, pixels: i32| {
+                    let first = start.clamp(0, pixels);
+                    first..start.saturating_add(size).clamp(first, pixels)
+                };
+                (range(x, width, frame_width), range(y, height, frame_height))
+            }
+            CropWindow::Normalized {
+                x,
+                y,
+                width,
+                height,
+            } => {
+                let range = |start: f32, size: f32, pixels: i32| {
+                    let first = ((start * pixels as f32).floor() as i32).clamp(0, pixels - 1);
+                    let end = ((start + size) * pixels as f32).ceil() as i32;
+                    first..end.clamp(first + 1, pixels)
+                };
+                (range(x, width, frame_width), range(y, height, frame_height))
+            }
+        }
+    }
+
+    fn validate(&self, frame_width: i32, frame_height: i32) -> Result<(), RenderError> {
+        let inside = match *self {
+            CropWindow::Pixels {
+                x,
+                y,
+                width,
+                height,
+            } => {
+                x >= 0
+                    && y >= 0
+                    && width > 0
+                    && height > 0
+                    && width <= frame_width - x
+                    && height <= frame_height - y
+            }
+            CropWindow::Normalized {
+                x,
+                y,
+                width,
+                height,
+            } => {
+                x >= 0.0
+                    && y >= 0.0
+                    && width > 0.0
+                    && height > 0.0
+                    && x + width <= 1.0
+                    && y + height <= 1.0
+            }
+        };
+
+        match inside {
+            true => Ok(()),
+            false => Err(RenderError::InvalidSettings(format!(
+                "crop window {:?} is empty or outside the {}x{} frame",
+                self, frame_width, frame_height
+            ))),
+        }
+    }
+}
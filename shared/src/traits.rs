@@ -1,17 +1,15 @@
+use crate::{
+    data::{Frame, Pixel},
+    error::RenderError,
+    settings::RenderSettings,
+};
+
 pub trait Render {
-    fn render(
-        &self,
-        frame_width: i32,
-        frame_height: i32,
-        samples_per_pixel: i16,
-    ) -> crate::data::Frame;
+    fn render(&self, settings: &RenderSettings) -> Result<Frame, RenderError>;
 
-    fn render_pixel(
-        &self,
-        x: i32,
-        y: i32,
-        frame_width: i32,
-        frame_height: i32,
-        samples_per_pixel: i16,
-    ) -> crate::data::Pixel;
+    /**
+     * Single pixel of the frame described by settings, x and y from the bottom left
+     */
+    fn render_pixel(&self, x: i32, y: i32, settings: &RenderSettings)
+        -> Result<Pixel, RenderError>;
 }
@@ -0,0 +1,158 @@
+use std::{fs, path::PathBuf};
+
+use shared::{
+    error::RenderError,
+    settings::{CropOutput, CropWindow, Integrator, RenderSettings},
+};
+
+fn with_crop(crop: CropWindow) -> RenderSettings {
+    RenderSettings {
+        width: 100,
+        height: 50,
+        crop: Some(crop),
+        ..Default::default()
+    }
+}
+
+fn pixels(x: i32, y: i32, width: i32, height: i32) -> CropWindow {
+    CropWindow::Pixels {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+/**
+ * File in the temporary directory, unique to this test process
+ */
+fn temp_file(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("shared-{}-{}", std::process::id(), name))
+}
+
+#[test]
+fn crop_windows_inside_the_frame_are_valid() {
+    for crop in [
+        pixels(0, 0, 100, 50),
+        pixels(99, 49, 1, 1),
+        CropWindow::Normalized {
+            x: 0.25,
+            y: 0.5,
+            width: 0.75,
+            height: 0.5,
+        },
+    ] {
+        assert_eq!(with_crop(crop).validate(), Ok(()), "{:?}", crop);
+    }
+}
+
+#[test]
+fn crop_windows_outside_the_frame_are_rejected() {
+    for crop in [
+        pixels(1, 1, i32::MAX, 2),
+        pixels(1, 1, 2, i32::MAX),
+        pixels(i32::MAX, 0, 1, 1),
+        pixels(-1, 0, 10, 10),
+        pixels(0, 0, 0, 10),
+        pixels(50, 0, 51, 10),
+        CropWindow::Normalized {
+            x: 0.5,
+            y: 0.0,
+            width: 0.6,
+            height: 1.0,
+        },
+        CropWindow::Normalized {
+            x: 0.0,
+            y: 0.0,
+            width: f32::NAN,
+            height: 1.0,
+        },
+    ] {
+        assert!(
+            matches!(
+                with_crop(crop).validate(),
+                Err(RenderError::InvalidSettings(_))
+            ),
+            "{:?}",
+            crop
+        );
+    }
+}
+
+#[test]
+fn pixel_ranges_stay_inside_the_frame() {
+    assert_eq!(pixels(10, 5, 20, 10).pixel_ranges(100, 50), (10..30, 5..15));
+    assert_eq!(
+        pixels(1, 1, i32::MAX, i32::MAX).pixel_ranges(100, 50),
+        (1..100, 1..50)
+    );
+    assert_eq!(pixels(-5, 60, 10, 10).pixel_ranges(100, 50), (0..5, 50..50));
+
+    // Partly covered pixels count as covered
+    let normalized = CropWindow::Normalized {
+        x: 0.1,
+        y: 0.0,
+        width: 0.25,
+        height: 0.01,
+    };
+    assert_eq!(normalized.pixel_ranges(10, 10), (1..4, 0..1));
+}
+
+#[test]
+fn output_size_follows_crop_output() {
+    let mut settings = with_crop(pixels(10, 5, 20, 10));
+    assert_eq!(settings.output_size(), (20, 10));
+
+    settings.crop_output = CropOutput::FullFrame;
+    assert_eq!(settings.output_size(), (100, 50));
+
+    settings.crop = None;
+    settings.crop_output = CropOutput::Cropped;
+    assert_eq!(settings.output_size(), (100, 50));
+}
+
+#[test]
+fn settings_survive_save_and_load() {
+    let settings = RenderSettings {
+        width: 64,
+        height: 48,
+        samples_per_pixel: 32,
+        max_depth: 8,
+        seed: Some(7),
+        threads: Some(2),
+        crop: Some(pixels(8, 4, 16, 12)),
+        crop_output: CropOutput::FullFrame,
+        integrator: Integrator::Spectral,
+    };
+    let path = temp_file("settings.json");
+
+    settings.save(&path).unwrap();
+    let loaded = RenderSettings::load(&path);
+    fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, Ok(settings));
+}
+
+#[test]
+fn loading_fills_missing_fields_and_checks_ranges() {
+    let path = temp_file("partial.json");
+
+    fs::write(&path, r#"{ "width": 64, "seed": 3 }"#).unwrap();
+    let partial = RenderSettings::load(&path);
+    fs::write(&path, r#"{ "samples_per_pixel": 0 }"#).unwrap();
+    let invalid = RenderSettings::load(&path);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        partial,
+        Ok(RenderSettings {
+            width: 64,
+            seed: Some(3),
+            ..Default::default()
+        })
+    );
+    assert!(matches!(invalid, Err(RenderError::InvalidSettings(_))));
+    assert!(matches!(
+        RenderSettings::load(&temp_file("missing.json")),
+        Err(RenderError::Io(_))
+    ));
+}